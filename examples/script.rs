@@ -0,0 +1,13 @@
+use enigo::{script, Enigo};
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    thread::sleep(Duration::from_secs(2));
+    let mut enigo = Enigo::new();
+
+    // move, click and write text
+    let actions = script::parse("move 500,200; click left; type Hello World!; key Return")
+        .expect("Could not parse script");
+    script::run(&mut enigo, &actions);
+}
@@ -72,11 +72,31 @@ pub use macos::Enigo;
 #[cfg(target_os = "linux")]
 mod linux;
 #[cfg(target_os = "linux")]
-pub use crate::linux::Enigo;
+pub use crate::linux::{
+    Backend, Barrier, ClickStrategy, DeviceType, Direction, Enigo, ErrorAction, FocusModel,
+    InputDevice, InputState, KeyGuard, ModifierCondition, NewlineMode, SEARCH_CLASS,
+    SEARCH_CLASSNAME, SEARCH_NAME, SEARCH_ROLE, SEARCH_TITLE, ScheduledAction, ThreadSafeEnigo,
+    TypingSchedule, VirtualKey, WindowAction, WindowIdentity, WindowInfo, XExtension,
+};
 
 /// DSL parser module
+///
+/// The DSL only describes keys and is evaluated while it is parsed. For
+/// clicks, mouse moves and pauses see [script](script/index.html).
 pub mod dsl;
 
+/// Action script module
+///
+/// Scripts parse into [Action](script/enum.Action.html)s first, which are
+/// run later, saved as macros or replayed with timing, and cover the mouse
+/// and pauses as well as the keyboard. The [dsl](dsl/index.html) can't be
+/// extended to that without breaking its syntax, where `{` and `}` already
+/// mark key tags and everything else is typed, so it stays for typing text
+/// with held keys, e.g. `{+SHIFT}hello{-SHIFT}`. Within a script, `type`
+/// plays the part of the DSL's plain text and `key_down`/`key_up` the part
+/// of its tags.
+pub mod script;
+
 #[cfg(feature = "with_serde")]
 #[macro_use]
 extern crate serde_derive;
@@ -99,6 +119,10 @@ pub enum MouseButton {
     Middle,
     /// Right mouse button
     Right,
+    /// Back side button (X button 8 on Linux, XBUTTON1 on Windows, button 3 on macOS)
+    Back,
+    /// Forward side button (X button 9 on Linux, XBUTTON2 on Windows, button 4 on macOS)
+    Forward,
 
     /// Scroll up button
     ScrollUp,
@@ -291,20 +315,75 @@ pub enum Key {
     F9,
     /// home key
     Home,
+    /// insert key (help key on macOS)
+    Insert,
     /// left arrow key
     LeftArrow,
+    /// media next track key
+    MediaNext,
+    /// media play/pause key
+    MediaPlayPause,
+    /// media previous track key
+    MediaPrev,
+    /// media stop playback key (not on macOS, which has none)
+    MediaStop,
+    /// menu key opening the context menu (also known as "apps" key)
+    Menu,
     /// meta key (also known as "windows", "super", and "command")
     Meta,
+    /// mode switch key on Linux, selecting the third symbol level of some
+    /// layouts (AltGr on Windows, right option key on macOS)
+    ModeSwitch,
+    /// num lock key (clear key on macOS)
+    NumLock,
+    /// numpad 0 key
+    Numpad0,
+    /// numpad 1 key
+    Numpad1,
+    /// numpad 2 key
+    Numpad2,
+    /// numpad 3 key
+    Numpad3,
+    /// numpad 4 key
+    Numpad4,
+    /// numpad 5 key
+    Numpad5,
+    /// numpad 6 key
+    Numpad6,
+    /// numpad 7 key
+    Numpad7,
+    /// numpad 8 key
+    Numpad8,
+    /// numpad 9 key
+    Numpad9,
+    /// numpad add key
+    NumpadAdd,
+    /// numpad decimal point key
+    NumpadDecimal,
+    /// numpad divide key
+    NumpadDivide,
+    /// numpad enter key (return key on Windows)
+    NumpadEnter,
+    /// numpad multiply key
+    NumpadMultiply,
+    /// numpad subtract key
+    NumpadSubtract,
     /// option key on macOS (alt key on Linux and Windows)
     Option,
     /// page down key
     PageDown,
     /// page up key
     PageUp,
+    /// pause key (not on macOS, which has none)
+    Pause,
+    /// print screen key (not on macOS, which has none)
+    PrintScreen,
     /// return key
     Return,
     /// right arrow key
     RightArrow,
+    /// scroll lock key (not on macOS, which has none)
+    ScrollLock,
     /// shift key
     Shift,
     /// space key
@@ -316,6 +395,12 @@ pub enum Key {
     Tab,
     /// up arrow key
     UpArrow,
+    /// volume down key
+    VolumeDown,
+    /// mute key
+    VolumeMute,
+    /// volume up key
+    VolumeUp,
     #[deprecated(since = "0.0.12", note = "now renamed to Meta")]
     /// windows key on Windows (super key on Linux, command key on macOS)
     Windows,
@@ -325,6 +410,92 @@ pub enum Key {
     Raw(u16),
 }
 
+impl From<char> for Key {
+    /// The layout dependent key typing `c`.
+    fn from(c: char) -> Self {
+        Key::Layout(c)
+    }
+}
+
+impl TryFrom<&str> for Key {
+    type Error = EnigoError;
+
+    /// Parse a single character as `Key::Layout` and anything else as the
+    /// X keysym name of a key, e.g. `Return`, `F5`, `Control` or
+    /// `Page_Down`. Other names fail with `UnknownKey`.
+    fn try_from(name: &str) -> Result<Self, EnigoError> {
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Key::Layout(c));
+        }
+        Ok(match name {
+            "Alt" => Key::Alt,
+            "BackSpace" => Key::Backspace,
+            "Caps_Lock" => Key::CapsLock,
+            "Control" => Key::Control,
+            "Delete" => Key::Delete,
+            "Down" => Key::DownArrow,
+            "End" => Key::End,
+            "Escape" => Key::Escape,
+            "F1" => Key::F1,
+            "F2" => Key::F2,
+            "F3" => Key::F3,
+            "F4" => Key::F4,
+            "F5" => Key::F5,
+            "F6" => Key::F6,
+            "F7" => Key::F7,
+            "F8" => Key::F8,
+            "F9" => Key::F9,
+            "F10" => Key::F10,
+            "F11" => Key::F11,
+            "F12" => Key::F12,
+            "Home" => Key::Home,
+            "Insert" => Key::Insert,
+            "KP_0" => Key::Numpad0,
+            "KP_1" => Key::Numpad1,
+            "KP_2" => Key::Numpad2,
+            "KP_3" => Key::Numpad3,
+            "KP_4" => Key::Numpad4,
+            "KP_5" => Key::Numpad5,
+            "KP_6" => Key::Numpad6,
+            "KP_7" => Key::Numpad7,
+            "KP_8" => Key::Numpad8,
+            "KP_9" => Key::Numpad9,
+            "KP_Add" => Key::NumpadAdd,
+            "KP_Decimal" => Key::NumpadDecimal,
+            "KP_Divide" => Key::NumpadDivide,
+            "KP_Enter" => Key::NumpadEnter,
+            "KP_Multiply" => Key::NumpadMultiply,
+            "KP_Subtract" => Key::NumpadSubtract,
+            "Left" => Key::LeftArrow,
+            "Menu" => Key::Menu,
+            "Mode_switch" => Key::ModeSwitch,
+            "Num_Lock" => Key::NumLock,
+            "Option" => Key::Option,
+            "Page_Down" => Key::PageDown,
+            "Page_Up" => Key::PageUp,
+            "Pause" => Key::Pause,
+            "Print" => Key::PrintScreen,
+            "Return" => Key::Return,
+            "Right" => Key::RightArrow,
+            "Scroll_Lock" => Key::ScrollLock,
+            "Shift" => Key::Shift,
+            "space" => Key::Space,
+            "Super" => Key::Meta,
+            "Tab" => Key::Tab,
+            "Up" => Key::UpArrow,
+            "XF86AudioLowerVolume" => Key::VolumeDown,
+            "XF86AudioMute" => Key::VolumeMute,
+            "XF86AudioNext" => Key::MediaNext,
+            "XF86AudioPlay" => Key::MediaPlayPause,
+            "XF86AudioPrev" => Key::MediaPrev,
+            "XF86AudioRaiseVolume" => Key::VolumeUp,
+            "XF86AudioStop" => Key::MediaStop,
+            _ => return Err(EnigoError::UnknownKey(name.to_string())),
+        })
+    }
+}
+
 /// Representing an interface and a set of keyboard functions every
 /// operating system implementation _should_ implement.
 pub trait KeyboardControllable {
@@ -391,6 +562,7 @@ impl Enigo {
     }
 }
 
+use std::convert::TryFrom;
 use std::fmt;
 
 impl fmt::Debug for Enigo {
@@ -398,3 +570,99 @@ impl fmt::Debug for Enigo {
         write!(f, "Enigo")
     }
 }
+
+/// An error that can occur when emitting events or querying the system
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnigoError {
+    /// When no monitor matches the requested name.
+    UnknownMonitor(String),
+
+    /// When the X server can't compile the requested keymap.
+    UnknownKeymap(String),
+
+    /// When another window than the expected one has the focus.
+    FocusMismatch {
+        /// the window that should have the focus
+        expected: i32,
+        /// the window that has the focus, if any
+        actual: Option<i32>,
+    },
+
+    /// When a window doesn't support or refuses a drag-and-drop.
+    DropRejected(i32),
+
+    /// When libxdo reports a failure with the given return code.
+    XdoFailed(i32),
+
+    /// When a string can't be passed on, because it contains a NUL byte.
+    InvalidInput(String),
+
+    /// When no accessible widget has the requested role and name.
+    UnknownAccessible {
+        /// the accessible role, e.g. `text`
+        role: String,
+        /// the accessible name
+        name: String,
+    },
+
+    /// When the X display with the given name can't be opened.
+    NoDisplay(String),
+
+    /// When a key name doesn't name any key.
+    UnknownKey(String),
+
+    /// When the input backend can't be set up or stops taking events, e.g.
+    /// because `/dev/uinput` isn't writable.
+    BackendFailed(String),
+
+    /// When the X server rejects a request, e.g. with `BadWindow` for a
+    /// window that was destroyed in the meantime.
+    XRequestFailed {
+        /// the X error code, e.g. 3 for `BadWindow`
+        code: u8,
+        /// the window or other resource the request was about
+        resource: u32,
+    },
+}
+impl std::error::Error for EnigoError {}
+impl fmt::Display for EnigoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnigoError::UnknownMonitor(name) => write!(f, "Unknown monitor: {}", name),
+            EnigoError::UnknownKeymap(name) => write!(f, "Unknown keymap: {}", name),
+            EnigoError::FocusMismatch { expected, actual: Some(actual) } => {
+                write!(f, "Window {} is focused instead of {}", actual, expected)
+            }
+            EnigoError::FocusMismatch { expected, actual: None } => {
+                write!(f, "No window is focused instead of {}", expected)
+            }
+            EnigoError::DropRejected(window) => write!(f, "Window {} rejected the drop", window),
+            EnigoError::XdoFailed(code) => write!(f, "libxdo failed with code {}", code),
+            EnigoError::InvalidInput(text) => write!(f, "Invalid input: {:?}", text),
+            EnigoError::UnknownAccessible { role, name } => {
+                write!(f, "No accessible {} named {:?}", role, name)
+            }
+            EnigoError::NoDisplay(name) => write!(f, "Can't open display {:?}", name),
+            EnigoError::UnknownKey(name) => write!(f, "Unknown key: {:?}", name),
+            EnigoError::BackendFailed(error) => write!(f, "Input backend failed: {}", error),
+            EnigoError::XRequestFailed { code, resource } => {
+                write!(f, "X error {} on resource {:#x}", code, resource)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_from_names() {
+        assert_eq!(Key::from('a'), Key::Layout('a'));
+        assert_eq!(Key::try_from("ä"), Ok(Key::Layout('ä')));
+        assert_eq!(Key::try_from("Page_Down"), Ok(Key::PageDown));
+        assert_eq!(Key::try_from("KP_Enter"), Ok(Key::NumpadEnter));
+        assert_eq!(Key::try_from("Retrun"), Err(EnigoError::UnknownKey("Retrun".into())));
+        assert_eq!(Key::try_from(""), Err(EnigoError::UnknownKey("".into())));
+    }
+}
@@ -1,14 +1,139 @@
 use libc;
 
-use crate::{Key, KeyboardControllable, MouseButton, MouseControllable};
+use crate::script::{self, Action, ScriptError};
+use crate::{EnigoError, Key, KeyboardControllable, MouseButton, MouseControllable};
 
-use self::libc::{c_char, c_int, c_uint, c_long, c_void, useconds_t};
-use std::{borrow::Cow, ffi::CString, ptr};
+use self::libc::{c_char, c_int, c_uchar, c_uint, c_long, c_ulong, c_void, useconds_t};
+#[cfg(feature = "with_serde")]
+use std::path::Path;
+use std::{
+    borrow::Cow,
+    ffi::{CStr, CString},
+    io,
+    ops::{Deref, DerefMut},
+    ptr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
 
-const CURRENT_WINDOW: c_int = 0;
+#[cfg(feature = "with_atspi")]
+mod atspi;
+mod selection;
+mod uinput;
+mod xdnd;
+mod xerror;
+mod xlib;
+mod xsettings;
+
+const CURRENT_WINDOW: Window = 0;
 const DEFAULT_DELAY: u64 = 12000;
-type Window = c_int;
+/// Match the pattern of [find_windows](struct.Enigo.html#method.find_windows)
+/// against the window title. Current libxdo treats this like `SEARCH_NAME`.
+/// This is Linux-specific.
+pub const SEARCH_TITLE: u32 = 1;
+/// Match the pattern of [find_windows](struct.Enigo.html#method.find_windows)
+/// against the window class, the second string in `WM_CLASS`.
+/// This is Linux-specific.
+pub const SEARCH_CLASS: u32 = 1 << 1;
+/// Match the pattern of [find_windows](struct.Enigo.html#method.find_windows)
+/// against the window name, i.e. its title.
+/// This is Linux-specific.
+pub const SEARCH_NAME: u32 = 1 << 2;
+const SEARCH_PID: c_uint = 1 << 3;
+/// Match the pattern of [find_windows](struct.Enigo.html#method.find_windows)
+/// against the window class name, the first string in `WM_CLASS`.
+/// This is Linux-specific.
+pub const SEARCH_CLASSNAME: u32 = 1 << 6;
+/// Match the pattern of [find_windows](struct.Enigo.html#method.find_windows)
+/// against the window role in `WM_WINDOW_ROLE`.
+/// This is Linux-specific.
+pub const SEARCH_ROLE: u32 = 1 << 8;
+const SEARCH_ALL: c_uint = 1;
+const VERIFY_RETRIES: u32 = 3;
+const ERROR_RETRIES: u32 = 3;
+// longest distance in pixels between two interpolated pointer positions
+const STROKE_STEP: f64 = 4.0;
+// how long measure_input_latency waits for the event
+const LATENCY_TIMEOUT: Duration = Duration::from_secs(1);
+// time between two frames of a touch gesture
+const TOUCH_FRAME: Duration = Duration::from_millis(10);
+// size of the virtual touchpad of swipe, and the space between its fingers
+const TOUCHPAD_SIZE: i32 = 2000;
+const FINGER_SPACING: i32 = 150;
+// how long key_click_and_get_focus waits for the focus to move
+const FOCUS_CHANGE_TIMEOUT: Duration = Duration::from_millis(200);
+// the repeat delay and rate XKB starts with
+const DEFAULT_AUTO_REPEAT: (Duration, u32) = (Duration::from_millis(660), 25);
+// time mouse_drag takes to move from start to end
+const DRAG_DURATION: Duration = Duration::from_millis(200);
+// _NET_WM_DESKTOP of windows shown on all desktops
+const ALL_DESKTOPS: u32 = 0xFFFF_FFFF;
+// double-click time of GTK, for desktops without an XSETTINGS manager
+const DEFAULT_DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+// selection owned by whoever holds the input lock
+const INPUT_LOCK_SELECTION: &str = "ENIGO_INPUT_LOCK";
+// how often wait_for_window_by_pid searches for the window
+const WINDOW_POLL: Duration = Duration::from_millis(50);
+// characters typed per libxdo call by key_sequence
+const TEXT_CHUNK: usize = 256;
+// both sides of each modifier released by release_all_modifiers
+const MODIFIER_KEYSYMS: &[&str] = &[
+    "Shift_L",
+    "Shift_R",
+    "Control_L",
+    "Control_R",
+    "Alt_L",
+    "Alt_R",
+    "Super_L",
+    "Super_R",
+    "ISO_Level3_Shift",
+];
+// keys and modifiers pressed by fuzz
+const FUZZ_KEYS: &[Key] = &[
+    Key::Layout('a'),
+    Key::Layout('e'),
+    Key::Layout('q'),
+    Key::Layout('z'),
+    Key::Layout('1'),
+    Key::Layout('.'),
+    Key::Space,
+    Key::Return,
+    Key::Tab,
+    Key::Backspace,
+    Key::Delete,
+    Key::Escape,
+    Key::UpArrow,
+    Key::DownArrow,
+    Key::LeftArrow,
+    Key::RightArrow,
+    Key::Home,
+    Key::End,
+    Key::F1,
+    Key::F4,
+];
+const FUZZ_MODIFIERS: &[Key] = &[Key::Control, Key::Alt, Key::Shift];
+
+// Modifier and button bits of the X11 input state mask
+const SHIFT_MASK: c_uint = 1 << 0;
+const LOCK_MASK: c_uint = 1 << 1;
+const CONTROL_MASK: c_uint = 1 << 2;
+const MOD1_MASK: c_uint = 1 << 3;
+const MOD2_MASK: c_uint = 1 << 4;
+const MOD4_MASK: c_uint = 1 << 6;
+const BUTTON1_MASK: c_uint = 1 << 8;
+const BUTTON2_MASK: c_uint = 1 << 9;
+const BUTTON3_MASK: c_uint = 1 << 10;
+const MODIFIER_MASKS: c_uint = SHIFT_MASK | CONTROL_MASK | MOD1_MASK | MOD4_MASK;
+const LOCK_MASKS: c_uint = LOCK_MASK | MOD2_MASK;
+const BUTTON_MASKS: c_uint = BUTTON1_MASK | BUTTON2_MASK | BUTTON3_MASK;
+type Window = c_ulong;
 type Xdo = *const c_void;
+type ErrorHandler = Box<dyn Fn(&EnigoError) -> ErrorAction + Send>;
 
 #[repr(C)]
 #[derive(Copy)]
@@ -51,7 +176,9 @@ extern "C" {
     fn xdo_free(xdo: Xdo);
     fn xdo_new(display: *const c_char) -> Xdo;
     fn xdo_focus_window(xdo: Xdo, window: Window) -> c_int;
+    fn xdo_activate_window(xdo: Xdo, window: Window) -> c_int;
     fn xdo_set_window_size(xdo: Xdo, window: Window, width: c_int, height: c_int, flags: c_int) -> c_int;
+    fn xdo_move_window(xdo: Xdo, window: Window, x: c_int, y: c_int) -> c_int;
     fn xdo_get_pid_window(xdo: Xdo, window: Window) -> c_int;
     fn xdo_search_windows(xdo: Xdo, search: *const c_void,
         windowlist_ret: *mut *mut Window, nwindows_ret: *mut c_uint) -> c_int;
@@ -60,6 +187,19 @@ extern "C" {
     fn xdo_mouse_up(xdo: Xdo, window: Window, button: c_int) -> c_int;
     fn xdo_move_mouse(xdo: Xdo, x: c_int, y: c_int, screen: c_int) -> c_int;
     fn xdo_move_mouse_relative(xdo: Xdo, x: c_int, y: c_int) -> c_int;
+    fn xdo_move_mouse_relative_to_window(xdo: Xdo, window: Window, x: c_int, y: c_int) -> c_int;
+    fn xdo_get_mouse_location(xdo: Xdo, x: *mut c_int, y: *mut c_int,
+        screen_num: *mut c_int) -> c_int;
+    fn xdo_get_input_state(xdo: Xdo) -> c_uint;
+    fn xdo_get_focused_window_sane(xdo: Xdo, window_ret: *mut Window) -> c_int;
+    fn xdo_get_window_location(xdo: Xdo, window: Window, x: *mut c_int, y: *mut c_int,
+        screen_ret: *mut *mut c_void) -> c_int;
+    fn xdo_get_window_size(xdo: Xdo, window: Window, width_ret: *mut c_uint,
+        height_ret: *mut c_uint) -> c_int;
+    fn xdo_get_viewport_dimensions(xdo: Xdo, width: *mut c_uint, height: *mut c_uint,
+        screen: c_int) -> c_int;
+    fn xdo_get_window_name(xdo: Xdo, window: Window, name_ret: *mut *mut c_uchar,
+        name_len_ret: *mut c_int, name_type: *mut c_int) -> c_int;
     fn xdo_enter_text_window(xdo: Xdo, 
         window: Window, string: *const c_char, delay: useconds_t) -> c_int;
     fn xdo_send_keysequence_window(xdo: Xdo, 
@@ -79,6 +219,620 @@ fn mousebutton(button: MouseButton) -> c_int {
         MouseButton::ScrollDown => 5,
         MouseButton::ScrollLeft => 6,
         MouseButton::ScrollRight => 7,
+        MouseButton::Back => 8,
+        MouseButton::Forward => 9,
+    }
+}
+
+/// A snapshot of the pointer position, held mouse buttons and modifier/lock
+/// state, as returned by
+/// [snapshot_state](struct.Enigo.html#method.snapshot_state).
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputState {
+    /// x coordinate of the pointer on the root window
+    pub x: i32,
+    /// y coordinate of the pointer on the root window
+    pub y: i32,
+    /// X11 mask of the held mouse buttons (Button1Mask..Button3Mask)
+    pub buttons: u32,
+    /// X11 mask of the held modifiers (Shift, Control, Mod1, Mod4)
+    pub modifiers: u32,
+    /// X11 mask of the active locks (Lock for CapsLock, Mod2 for NumLock)
+    pub locks: u32,
+}
+
+/// A required modifier state for
+/// [key_click_if](struct.Enigo.html#method.key_click_if).
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModifierCondition {
+    /// shift must not be held
+    ShiftUp,
+    /// shift must be held
+    ShiftDown,
+    /// control must not be held
+    CtrlUp,
+    /// control must be held
+    CtrlDown,
+    /// alt must not be held
+    AltUp,
+    /// alt must be held
+    AltDown,
+    /// meta (super) must not be held
+    MetaUp,
+    /// meta (super) must be held
+    MetaDown,
+}
+
+impl ModifierCondition {
+    fn is_met(self, mask: c_uint) -> bool {
+        let (modifier, held) = match self {
+            ModifierCondition::ShiftUp => (SHIFT_MASK, false),
+            ModifierCondition::ShiftDown => (SHIFT_MASK, true),
+            ModifierCondition::CtrlUp => (CONTROL_MASK, false),
+            ModifierCondition::CtrlDown => (CONTROL_MASK, true),
+            ModifierCondition::AltUp => (MOD1_MASK, false),
+            ModifierCondition::AltDown => (MOD1_MASK, true),
+            ModifierCondition::MetaUp => (MOD4_MASK, false),
+            ModifierCondition::MetaDown => (MOD4_MASK, true),
+        };
+        (mask & modifier != 0) == held
+    }
+}
+
+/// How a window takes the keyboard focus according to the ICCCM, as returned
+/// by [window_focus_model](struct.Enigo.html#method.window_focus_model).
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FocusModel {
+    /// The window never takes keyboard input.
+    NoInput,
+    /// The window accepts the focus when it is set on it.
+    Passive,
+    /// The window accepts the focus and also wants `WM_TAKE_FOCUS`, e.g.
+    /// to move the focus to one of its subwindows.
+    LocallyActive,
+    /// The window only takes the focus itself when sent `WM_TAKE_FOCUS`.
+    GloballyActive,
+}
+
+/// An operation the window manager allows on a window according to EWMH, as
+/// returned by
+/// [window_allowed_actions](struct.Enigo.html#method.window_allowed_actions).
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowAction {
+    /// The window can be moved.
+    Move,
+    /// The window can be resized.
+    Resize,
+    /// The window can be iconified.
+    Minimize,
+    /// The window can be rolled up to its title bar.
+    Shade,
+    /// The window can be put on all desktops.
+    Stick,
+    /// The window can be maximized horizontally.
+    MaximizeHorizontally,
+    /// The window can be maximized vertically.
+    MaximizeVertically,
+    /// The window can be made fullscreen.
+    Fullscreen,
+    /// The window can be moved to another desktop.
+    ChangeDesktop,
+    /// The window can be closed.
+    Close,
+    /// The window can be kept above other windows.
+    Above,
+    /// The window can be kept below other windows.
+    Below,
+}
+
+impl WindowAction {
+    fn from_atom_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "_NET_WM_ACTION_MOVE" => WindowAction::Move,
+            "_NET_WM_ACTION_RESIZE" => WindowAction::Resize,
+            "_NET_WM_ACTION_MINIMIZE" => WindowAction::Minimize,
+            "_NET_WM_ACTION_SHADE" => WindowAction::Shade,
+            "_NET_WM_ACTION_STICK" => WindowAction::Stick,
+            "_NET_WM_ACTION_MAXIMIZE_HORZ" => WindowAction::MaximizeHorizontally,
+            "_NET_WM_ACTION_MAXIMIZE_VERT" => WindowAction::MaximizeVertically,
+            "_NET_WM_ACTION_FULLSCREEN" => WindowAction::Fullscreen,
+            "_NET_WM_ACTION_CHANGE_DESKTOP" => WindowAction::ChangeDesktop,
+            "_NET_WM_ACTION_CLOSE" => WindowAction::Close,
+            "_NET_WM_ACTION_ABOVE" => WindowAction::Above,
+            "_NET_WM_ACTION_BELOW" => WindowAction::Below,
+            _ => return None,
+        })
+    }
+}
+
+/// How [mouse_click](trait.MouseControllable.html#tymethod.mouse_click)
+/// delivers the click.
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClickStrategy {
+    /// Click through `xdo_click_window` on the configured window. With the
+    /// default window (0) this is the same as a click at the pointer.
+    WindowTargeted,
+    /// Press and release the button at the current pointer position,
+    /// regardless of the configured window. Use this for applications that
+    /// read the global pointer state.
+    PointerPress,
+}
+
+/// How adaptive typing slows down once characters get dropped, see
+/// [set_adaptive_typing](struct.Enigo.html#method.set_adaptive_typing).
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypingSchedule {
+    /// delay per character in microseconds to start from, 1000 by default
+    pub start: u64,
+    /// factor the delay grows by on every drop, 2 by default
+    pub factor: u32,
+    /// longest delay per character in microseconds, 100000 by default
+    pub ceiling: u64,
+    /// number of texts in a row typed without drops after which the delay
+    /// goes back to `start`, never if 0, which is the default
+    pub reset_after: u32,
+}
+
+impl Default for TypingSchedule {
+    fn default() -> Self {
+        TypingSchedule {
+            start: 1000,
+            factor: 2,
+            ceiling: 100_000,
+            reset_after: 0,
+        }
+    }
+}
+
+impl TypingSchedule {
+    // The delay after a drop at `delay`
+    fn slowed(self, delay: u64) -> u64 {
+        delay.max(1).saturating_mul(u64::from(self.factor)).min(self.ceiling)
+    }
+}
+
+/// How an [Enigo](struct.Enigo.html) instance injects input, see
+/// [with_backend](struct.Enigo.html#method.with_backend).
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// XTest through libxdo, reaching the clients of the X server. This is
+    /// the default.
+    Xdo,
+    /// A virtual keyboard and mouse through the kernel's uinput module. Its
+    /// events pass the input stack like those of real devices, so they also
+    /// reach native Wayland clients and applications that ignore XTest.
+    Uinput,
+}
+
+/// The role of an [InputDevice](struct.InputDevice.html) in XInput2.
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceType {
+    /// a virtual pointer with a cursor on screen
+    MasterPointer,
+    /// a virtual keyboard with its own focus
+    MasterKeyboard,
+    /// a physical pointer attached to a master pointer
+    SlavePointer,
+    /// a physical keyboard attached to a master keyboard
+    SlaveKeyboard,
+    /// a physical device not attached to any master
+    FloatingSlave,
+}
+
+/// An input device as listed by
+/// [list_input_devices](struct.Enigo.html#method.list_input_devices).
+/// This is Linux-specific.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputDevice {
+    /// XInput2 device ID
+    pub id: i32,
+    /// device name, e.g. `Virtual core pointer`
+    pub name: String,
+    /// role of the device
+    pub device_type: DeviceType,
+}
+
+/// An XFixes pointer barrier created by
+/// [create_pointer_barrier](struct.Enigo.html#method.create_pointer_barrier).
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Barrier {
+    /// XID of the barrier
+    pub id: u32,
+    /// one end of the line the pointer can't cross
+    pub from: (i32, i32),
+    /// the other end of the line
+    pub to: (i32, i32),
+}
+
+/// An X extension that some enigo features rely on, see
+/// [has_extension](struct.Enigo.html#method.has_extension).
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum XExtension {
+    /// `XTEST`, used by libxdo to fake input
+    XTest,
+    /// `XInputExtension`, which provides XInput2 on current X servers
+    XInput2,
+    /// `RANDR`, used for the monitor layout
+    RandR,
+    /// `XKEYBOARD`, used for keyboard layouts and key positions
+    Xkb,
+    /// `XFIXES`, used for the cursor name
+    XFixes,
+    /// `MIT-SCREEN-SAVER`, used to keep the screen awake
+    ScreenSaver,
+}
+
+impl XExtension {
+    fn name(self) -> &'static str {
+        match self {
+            XExtension::XTest => "XTEST",
+            XExtension::XInput2 => "XInputExtension",
+            XExtension::RandR => "RANDR",
+            XExtension::Xkb => "XKEYBOARD",
+            XExtension::XFixes => "XFIXES",
+            XExtension::ScreenSaver => "MIT-SCREEN-SAVER",
+        }
+    }
+}
+
+/// How [type_multiline](struct.Enigo.html#method.type_multiline) enters
+/// line breaks.
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NewlineMode {
+    /// Type `\n` like any other character.
+    Literal,
+    /// Click the Return key between lines, which may end in `\n`, `\r\n`
+    /// or `\r`.
+    ReturnKey,
+}
+
+/// A key identified by its physical position, named after the key in that
+/// position on a US QWERTY keyboard. E.g. `VirtualKey::W` is the key right of
+/// Tab, which types `z` on an AZERTY layout.
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum VirtualKey {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Grave,
+    Minus,
+    Equal,
+    LeftBracket,
+    RightBracket,
+    Backslash,
+    Semicolon,
+    Apostrophe,
+    Comma,
+    Period,
+    Slash,
+    Space,
+}
+
+impl VirtualKey {
+    // XKB name of the key in this position
+    fn xkb_name(self) -> &'static str {
+        match self {
+            VirtualKey::A => "AC01",
+            VirtualKey::B => "AB05",
+            VirtualKey::C => "AB03",
+            VirtualKey::D => "AC03",
+            VirtualKey::E => "AD03",
+            VirtualKey::F => "AC04",
+            VirtualKey::G => "AC05",
+            VirtualKey::H => "AC06",
+            VirtualKey::I => "AD08",
+            VirtualKey::J => "AC07",
+            VirtualKey::K => "AC08",
+            VirtualKey::L => "AC09",
+            VirtualKey::M => "AB07",
+            VirtualKey::N => "AB06",
+            VirtualKey::O => "AD09",
+            VirtualKey::P => "AD10",
+            VirtualKey::Q => "AD01",
+            VirtualKey::R => "AD04",
+            VirtualKey::S => "AC02",
+            VirtualKey::T => "AD05",
+            VirtualKey::U => "AD07",
+            VirtualKey::V => "AB04",
+            VirtualKey::W => "AD02",
+            VirtualKey::X => "AB02",
+            VirtualKey::Y => "AD06",
+            VirtualKey::Z => "AB01",
+            VirtualKey::Digit0 => "AE10",
+            VirtualKey::Digit1 => "AE01",
+            VirtualKey::Digit2 => "AE02",
+            VirtualKey::Digit3 => "AE03",
+            VirtualKey::Digit4 => "AE04",
+            VirtualKey::Digit5 => "AE05",
+            VirtualKey::Digit6 => "AE06",
+            VirtualKey::Digit7 => "AE07",
+            VirtualKey::Digit8 => "AE08",
+            VirtualKey::Digit9 => "AE09",
+            VirtualKey::Grave => "TLDE",
+            VirtualKey::Minus => "AE11",
+            VirtualKey::Equal => "AE12",
+            VirtualKey::LeftBracket => "AD11",
+            VirtualKey::RightBracket => "AD12",
+            VirtualKey::Backslash => "BKSL",
+            VirtualKey::Semicolon => "AC10",
+            VirtualKey::Apostrophe => "AC11",
+            VirtualKey::Comma => "AB08",
+            VirtualKey::Period => "AB09",
+            VirtualKey::Slash => "AB10",
+            VirtualKey::Space => "SPCE",
+        }
+    }
+}
+
+/// The direction of a [swipe](struct.Enigo.html#method.swipe).
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// The fingers move up.
+    Up,
+    /// The fingers move down.
+    Down,
+    /// The fingers move to the left.
+    Left,
+    /// The fingers move to the right.
+    Right,
+}
+
+/// Everything enigo can tell about a window, as returned by
+/// [window_info](struct.Enigo.html#method.window_info).
+/// This is Linux-specific.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WindowInfo {
+    /// window ID
+    pub id: i32,
+    /// window title (`_NET_WM_NAME` or `WM_NAME`)
+    pub name: Option<String>,
+    /// class part of `WM_CLASS`
+    pub class: Option<String>,
+    /// instance part of `WM_CLASS`
+    pub class_name: Option<String>,
+    /// `WM_WINDOW_ROLE`
+    pub role: Option<String>,
+    /// `_NET_WM_PID`, 0 if unknown
+    pub pid: i32,
+    /// x, y, width and height of the window
+    pub geometry: (i32, i32, u32, u32),
+    /// whether the window is mapped and all its ancestors are mapped
+    pub viewable: bool,
+    /// atom names of `_NET_WM_STATE`, e.g. `_NET_WM_STATE_FULLSCREEN`
+    pub state: Vec<String>,
+}
+
+/// The attributes of a window that survive a restart of its application, as
+/// returned by [window_identity](struct.Enigo.html#method.window_identity)
+/// and found again by
+/// [resolve_identity](struct.Enigo.html#method.resolve_identity).
+/// Attributes that are `None` aren't compared.
+/// This is Linux-specific.
+#[cfg_attr(feature = "with_serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WindowIdentity {
+    /// class part of `WM_CLASS`
+    pub class: Option<String>,
+    /// instance part of `WM_CLASS`
+    pub class_name: Option<String>,
+    /// `WM_WINDOW_ROLE`
+    pub role: Option<String>,
+    /// regular expression the window title has to match, e.g. ` - Mozilla
+    /// Firefox$` (case-insensitive, like all libxdo searches)
+    pub title_pattern: Option<String>,
+    /// name of the process owning the window, as in `/proc/PID/comm`
+    pub process_name: Option<String>,
+}
+
+// A RandR monitor rectangle on the root window
+struct Monitor {
+    name: String,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl Monitor {
+    fn contains(&self, x: i32, y: i32) -> bool {
+        let (x, y) = (i64::from(x), i64::from(y));
+        x >= i64::from(self.x)
+            && y >= i64::from(self.y)
+            && x < i64::from(self.x) + i64::from(self.width)
+            && y < i64::from(self.y) + i64::from(self.height)
+    }
+}
+
+/// What to do about a failed operation, as decided by the
+/// [error handler](struct.Enigo.html#method.set_error_handler).
+/// This is Linux-specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorAction {
+    /// Run the operation again, up to three times.
+    Retry,
+    /// Carry on as if the operation had succeeded.
+    Ignore,
+    /// Give up on the operation. Methods returning a `Result` return the
+    /// error.
+    Abort,
+}
+
+/// An action waiting to be executed, as returned by
+/// [schedule](struct.Enigo.html#method.schedule).
+/// Dropping the handle doesn't cancel the action.
+/// This is Linux-specific.
+#[derive(Debug)]
+pub struct ScheduledAction {
+    cancel: mpsc::Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl ScheduledAction {
+    /// Cancel the action unless it is already due.
+    pub fn cancel(self) {
+        let _ = self.cancel.send(());
+    }
+    /// Wait until the action was executed or cancelled.
+    pub fn join(self) {
+        let _ = self.thread.join();
+    }
+}
+
+/// A key held down by [key_hold](struct.Enigo.html#method.key_hold),
+/// released again when the guard is dropped, even on an early return or a
+/// panic. The guard dereferences to the Enigo instance for the input sent
+/// while the key is held.
+/// This is Linux-specific.
+pub struct KeyGuard<'a> {
+    enigo: &'a mut Enigo,
+    key: Key,
+}
+
+impl Deref for KeyGuard<'_> {
+    type Target = Enigo;
+
+    fn deref(&self) -> &Enigo {
+        self.enigo
+    }
+}
+
+impl DerefMut for KeyGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Enigo {
+        self.enigo
+    }
+}
+
+impl Drop for KeyGuard<'_> {
+    fn drop(&mut self) {
+        self.enigo.key_up(self.key);
+    }
+}
+
+/// An [Enigo](struct.Enigo.html) instance that can be shared between
+/// threads, e.g. behind an `Arc` in a server taking automation commands:
+///
+/// ```no_run
+/// # use enigo::{Enigo, KeyboardControllable, ThreadSafeEnigo};
+/// # use std::{sync::Arc, thread};
+/// let shared = Arc::new(ThreadSafeEnigo::new(Enigo::new()));
+/// let worker = Arc::clone(&shared);
+/// thread::spawn(move || (&*worker).key_sequence("hello"));
+/// shared.lock().set_delay(0);
+/// ```
+///
+/// Enigo itself isn't `Sync`: its methods taking `&self` talk to the X
+/// server through the Xlib display of libxdo, and Xlib only guards a display
+/// against concurrent use if `XInitThreads` ran before any other Xlib call
+/// in the process, which a library can't ensure. The wrapper serializes all
+/// access through a mutex instead. The trait methods are implemented for
+/// `&ThreadSafeEnigo`, [lock](#method.lock) gives access to the rest.
+/// This is Linux-specific.
+pub struct ThreadSafeEnigo {
+    enigo: Mutex<Enigo>,
+}
+
+impl ThreadSafeEnigo {
+    /// Wrap `enigo`.
+    pub fn new(enigo: Enigo) -> Self {
+        ThreadSafeEnigo {
+            enigo: Mutex::new(enigo),
+        }
+    }
+    /// Get the wrapped instance for as long as the guard lives, blocking
+    /// other threads meanwhile. A thread that panicked with the guard
+    /// doesn't make the instance unusable.
+    pub fn lock(&self) -> MutexGuard<'_, Enigo> {
+        self.enigo.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    /// Unwrap the instance.
+    pub fn into_inner(self) -> Enigo {
+        self.enigo.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl MouseControllable for &ThreadSafeEnigo {
+    fn mouse_move_to(&mut self, x: i32, y: i32) {
+        self.lock().mouse_move_to(x, y);
+    }
+    fn mouse_move_relative(&mut self, x: i32, y: i32) {
+        self.lock().mouse_move_relative(x, y);
+    }
+    fn mouse_down(&mut self, button: MouseButton) {
+        self.lock().mouse_down(button);
+    }
+    fn mouse_up(&mut self, button: MouseButton) {
+        self.lock().mouse_up(button);
+    }
+    fn mouse_click(&mut self, button: MouseButton) {
+        self.lock().mouse_click(button);
+    }
+    fn mouse_scroll_x(&mut self, length: i32) {
+        self.lock().mouse_scroll_x(length);
+    }
+    fn mouse_scroll_y(&mut self, length: i32) {
+        self.lock().mouse_scroll_y(length);
+    }
+}
+
+impl KeyboardControllable for &ThreadSafeEnigo {
+    fn key_sequence(&mut self, sequence: &str) {
+        self.lock().key_sequence(sequence);
+    }
+    fn key_down(&mut self, key: Key) {
+        self.lock().key_down(key);
+    }
+    fn key_up(&mut self, key: Key) {
+        self.lock().key_up(key);
+    }
+    fn key_click(&mut self, key: Key) {
+        self.lock().key_click(key);
     }
 }
 
@@ -86,23 +840,151 @@ fn mousebutton(button: MouseButton) -> c_int {
 pub struct Enigo {
     xdo: Xdo,
     delay: u64,
-    window: i32,    
+    mouse_delay: u64,
+    window: i32,
+    scale_factor: f64,
+    invert_scroll_x: bool,
+    invert_scroll_y: bool,
+    click_strategy: ClickStrategy,
+    cancel: Option<Arc<AtomicBool>>,
+    adaptive_typing: bool,
+    typing_delay: u64,
+    typing_schedule: TypingSchedule,
+    clean_typings: u32,
+    touch: Option<uinput::TouchDevice>,
+    touchpad: Option<uinput::TouchDevice>,
+    screensaver_inhibited: bool,
+    modifier_settle: Duration,
+    error_handler: Option<Arc<Mutex<ErrorHandler>>>,
+    display_name: Option<CString>,
+    step_delay: Duration,
+    step_depth: u32,
+    barriers: Vec<Barrier>,
+    scroll_step: u32,
+    input_lock: Option<Window>,
+    anchor: (i32, i32),
+    virtual_input: Option<uinput::VirtualInput>,
+    held: Vec<Key>,
 }
-// This is safe, we have a unique pointer.
+// This is safe, we have a unique pointer and the display behind it is only
+// ever used by the thread owning the instance, see ThreadSafeEnigo for why
+// Enigo isn't Sync.
 // TODO: use Unique<c_char> once stable.
 unsafe impl Send for Enigo {}
 
 impl Default for Enigo {
     /// Create a new Enigo instance
+    ///
+    /// Panics if the display in `$DISPLAY` can't be opened, see
+    /// [try_new](struct.Enigo.html#method.try_new) for a checked version.
     fn default() -> Self {
+        match Self::try_new() {
+            Ok(enigo) => enigo,
+            Err(error) => panic!("enigo: {}", error),
+        }
+    }
+}
+impl Enigo {
+    /// Create a new Enigo instance on the X display in `$DISPLAY`.
+    ///
+    /// Returns `NoDisplay` if the display can't be opened, e.g. because
+    /// `$DISPLAY` isn't set or no X server runs there.
+    /// This is Linux-specific.
+    pub fn try_new() -> Result<Self, EnigoError> {
+        let enigo = Self::connect(None);
+        if enigo.xdo.is_null() {
+            return Err(EnigoError::NoDisplay(std::env::var("DISPLAY").unwrap_or_default()));
+        }
+        Ok(enigo)
+    }
+    /// Create a new Enigo instance on the X display `display`, e.g. `:99`
+    /// for an Xvfb server, instead of the one in `$DISPLAY`.
+    ///
+    /// Returns `NoDisplay` if the display can't be opened.
+    /// This is Linux-specific.
+    pub fn with_display(display: &str) -> Result<Self, EnigoError> {
+        let name =
+            CString::new(display).map_err(|_| EnigoError::InvalidInput(display.to_string()))?;
+        let enigo = Self::connect(Some(name));
+        if enigo.xdo.is_null() {
+            return Err(EnigoError::NoDisplay(display.to_string()));
+        }
+        Ok(enigo)
+    }
+    /// Create a new Enigo instance on the X display in `$DISPLAY` that
+    /// injects input through `backend`, instead of through libxdo.
+    ///
+    /// With `Backend::Uinput` the keys and mouse events of the
+    /// [KeyboardControllable](trait.KeyboardControllable.html) and
+    /// [MouseControllable](trait.MouseControllable.html) traits and of the
+    /// Linux-specific methods go through a virtual device covering the X
+    /// screen. The X display is still needed to map keys through the
+    /// current keymap and to find and inspect windows.
+    ///
+    /// Returns `NoDisplay` if the display can't be opened and
+    /// `BackendFailed` if the backend can't be set up, e.g. because
+    /// `/dev/uinput` isn't writable.
+    /// This is Linux-specific.
+    pub fn with_backend(backend: Backend) -> Result<Self, EnigoError> {
+        let mut enigo = Self::try_new()?;
+        if backend == Backend::Uinput {
+            enigo.open_virtual_input()?;
+        }
+        Ok(enigo)
+    }
+
+    fn open_virtual_input(&mut self) -> Result<(), EnigoError> {
+        let (width, height) = self.root_size();
+        let device = uinput::VirtualInput::new(width, height)
+            .map_err(|error| EnigoError::BackendFailed(error.to_string()))?;
+        self.virtual_input = Some(device);
+        Ok(())
+    }
+
+    fn connect(display_name: Option<CString>) -> Self {
+        let name = display_name.as_ref().map_or(ptr::null(), |name| name.as_ptr());
         Self {
-            xdo: unsafe { xdo_new(ptr::null()) },
+            xdo: unsafe { xdo_new(name) },
             delay: DEFAULT_DELAY,
-            window: CURRENT_WINDOW,
+            mouse_delay: DEFAULT_DELAY,
+            window: CURRENT_WINDOW as i32,
+            scale_factor: 1.0,
+            invert_scroll_x: false,
+            invert_scroll_y: false,
+            click_strategy: ClickStrategy::WindowTargeted,
+            cancel: None,
+            adaptive_typing: false,
+            typing_delay: TypingSchedule::default().start,
+            typing_schedule: TypingSchedule::default(),
+            clean_typings: 0,
+            touch: None,
+            touchpad: None,
+            screensaver_inhibited: false,
+            modifier_settle: Duration::from_micros(DEFAULT_DELAY),
+            error_handler: None,
+            display_name,
+            step_delay: Duration::from_secs(0),
+            step_depth: 0,
+            barriers: Vec::new(),
+            scroll_step: 1,
+            input_lock: None,
+            anchor: (0, 0),
+            virtual_input: None,
+            held: Vec::new(),
         }
     }
 }
 impl Enigo {
+    /// Get the backend injecting the input, see
+    /// [with_backend](#method.with_backend).
+    /// This is Linux-specific.
+    pub fn backend(&self) -> Backend {
+        if self.virtual_input.is_some() {
+            Backend::Uinput
+        } else {
+            Backend::Xdo
+        }
+    }
     /// Get the delay per keypress.
     /// Default value is 12000.
     /// This is Linux-specific.
@@ -114,6 +996,171 @@ impl Enigo {
     pub fn set_delay(&mut self, delay: u64) {
         self.delay = delay;
     }
+    /// Get the time in microseconds a mouse button is held down by
+    /// [mouse_click](trait.MouseControllable.html#tymethod.mouse_click),
+    /// which is also the pause between scroll steps.
+    /// Default value is 12000.
+    /// This is Linux-specific.
+    pub fn mouse_delay(&self) -> u64 {
+        self.mouse_delay
+    }
+    /// Set the time in microseconds a mouse button is held down by
+    /// [mouse_click](trait.MouseControllable.html#tymethod.mouse_click),
+    /// which is also the pause between scroll steps, e.g. for applications
+    /// that drop rapid clicks. It doesn't affect the keyboard
+    /// [delay](#method.delay).
+    /// This is Linux-specific.
+    pub fn set_mouse_delay(&mut self, delay: u64) {
+        self.mouse_delay = delay;
+    }
+    /// Get the time waited after pressing the modifiers of a
+    /// [key_combo](#method.key_combo) before pressing its key.
+    /// Default value is 12ms.
+    /// This is Linux-specific.
+    pub fn modifier_settle(&self) -> Duration {
+        self.modifier_settle
+    }
+    /// Set the time waited after pressing the modifiers of a
+    /// [key_combo](#method.key_combo) before pressing its key, for
+    /// applications that misread a chord when the key follows too quickly.
+    /// This is Linux-specific.
+    pub fn set_modifier_settle(&mut self, d: Duration) {
+        self.modifier_settle = d;
+    }
+    /// Get the pause after every mouse and keyboard operation.
+    /// Default value is zero.
+    /// This is Linux-specific.
+    pub fn global_step_delay(&self) -> Duration {
+        self.step_delay
+    }
+    /// Set a pause made after every operation of the
+    /// [MouseControllable](trait.MouseControllable.html) and
+    /// [KeyboardControllable](trait.KeyboardControllable.html) traits and
+    /// of the Linux-specific methods sending input, e.g. to run a whole
+    /// script at an observable pace for a demo. Unlike
+    /// [set_delay](#method.set_delay) this slows down moves and clicks too.
+    ///
+    /// The pause comes once at the end of an operation, however many events
+    /// it sends. The actions of [run](#method.run) and the steps of
+    /// [fuzz](#method.fuzz) count as operations of their own.
+    /// This is Linux-specific.
+    pub fn set_global_step_delay(&mut self, d: Duration) {
+        self.step_delay = d;
+    }
+    /// Get whether adaptive typing is enabled.
+    /// Default value is false.
+    /// This is Linux-specific.
+    pub fn adaptive_typing(&self) -> bool {
+        self.adaptive_typing
+    }
+    /// Let [key_sequence](trait.KeyboardControllable.html#tymethod.key_sequence)
+    /// and the other typing methods start typing fast and slow down once
+    /// characters get dropped, instead of using the fixed `delay`.
+    ///
+    /// Drops are only detected by [type_and_verify](#method.type_and_verify),
+    /// which can read the typed text back. It slows the typing delay down
+    /// along the [typing schedule](#method.set_typing_schedule) before
+    /// typing the missing tail again, and the slower delay is kept for all
+    /// text typed afterwards. Enabling it again starts from the fast delay.
+    /// This is Linux-specific.
+    pub fn set_adaptive_typing(&mut self, enabled: bool) {
+        self.adaptive_typing = enabled;
+        self.typing_delay = self.typing_schedule.start;
+        self.clean_typings = 0;
+    }
+    /// Get how adaptive typing slows down.
+    /// Default value is `TypingSchedule::default()`.
+    /// This is Linux-specific.
+    pub fn typing_schedule(&self) -> TypingSchedule {
+        self.typing_schedule
+    }
+    /// Set how [adaptive typing](#method.set_adaptive_typing) slows down
+    /// and when it speeds up again. The typing delay starts over from the
+    /// schedule's `start`.
+    /// This is Linux-specific.
+    pub fn set_typing_schedule(&mut self, schedule: TypingSchedule) {
+        self.typing_schedule = schedule;
+        self.typing_delay = schedule.start;
+        self.clean_typings = 0;
+    }
+    /// Get the scale factor applied to absolute mouse coordinates.
+    /// Default value is 1.0.
+    /// This is Linux-specific.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+    /// Set the scale factor applied to absolute mouse coordinates.
+    ///
+    /// Every absolute move multiplies its coordinates by `factor` before
+    /// handing them to xdo, so coordinates recorded on a 1x display can be
+    /// replayed on a 2x display with `set_scale_factor(2.0)`.
+    /// This is Linux-specific.
+    pub fn set_scale_factor(&mut self, factor: f64) {
+        self.scale_factor = factor;
+    }
+    /// Get whether the horizontal and vertical scroll directions are
+    /// inverted, as `(invert_x, invert_y)`.
+    /// Default value is `(false, false)`.
+    /// This is Linux-specific.
+    pub fn scroll_inversion(&self) -> (bool, bool) {
+        (self.invert_scroll_x, self.invert_scroll_y)
+    }
+    /// Invert the direction of
+    /// [mouse_scroll_x](trait.MouseControllable.html#tymethod.mouse_scroll_x)
+    /// and
+    /// [mouse_scroll_y](trait.MouseControllable.html#tymethod.mouse_scroll_y)
+    /// independently of each other.
+    /// This is Linux-specific.
+    pub fn set_scroll_inversion(&mut self, invert_x: bool, invert_y: bool) {
+        self.invert_scroll_x = invert_x;
+        self.invert_scroll_y = invert_y;
+    }
+    /// Get the number of wheel notches scrolled per unit of length.
+    /// Default value is 1.
+    /// This is Linux-specific.
+    pub fn scroll_step(&self) -> u32 {
+        self.scroll_step
+    }
+    /// Set the number of wheel notches that
+    /// [mouse_scroll_x](trait.MouseControllable.html#tymethod.mouse_scroll_x)
+    /// and
+    /// [mouse_scroll_y](trait.MouseControllable.html#tymethod.mouse_scroll_y)
+    /// scroll per unit of length, for long scrolls. A step of 0 is taken
+    /// as 1, so scrolling never turns into a no-op.
+    /// This is Linux-specific.
+    pub fn set_scroll_step(&mut self, step: u32) {
+        self.scroll_step = step.max(1);
+    }
+    /// Get how mouse clicks are delivered.
+    /// Default value is `ClickStrategy::WindowTargeted`.
+    /// This is Linux-specific.
+    pub fn click_strategy(&self) -> ClickStrategy {
+        self.click_strategy
+    }
+    /// Set how mouse clicks are delivered.
+    /// This is Linux-specific.
+    pub fn set_click_strategy(&mut self, strategy: ClickStrategy) {
+        self.click_strategy = strategy;
+    }
+    /// Set a handler that decides what happens when an operation fails,
+    /// e.g. when libxdo can't send an event or a window isn't focused.
+    ///
+    /// Without a handler operations give up right away, so that methods
+    /// returning a `Result` return the error.
+    /// This is Linux-specific.
+    pub fn set_error_handler(&mut self, handler: Box<dyn Fn(&EnigoError) -> ErrorAction + Send>) {
+        self.error_handler = Some(Arc::new(Mutex::new(handler)));
+    }
+    /// Set a flag that aborts long-running operations like
+    /// [mouse_stroke](#method.mouse_stroke), [run](#method.run) or
+    /// [wait_until](#method.wait_until) between two steps once it is set to
+    /// true, e.g. from a thread watching for a panic button.
+    ///
+    /// The flag isn't reset by enigo, store false in it to carry on.
+    /// This is Linux-specific.
+    pub fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.cancel = Some(flag);
+    }
     /// Get the window ID.
     /// Default value is 0.
     /// This is Linux-specific.
@@ -125,120 +1172,3109 @@ impl Enigo {
     pub fn set_window(&mut self, window: i32) {
         self.window = window;
     }
+    /// Ask the window manager to switch to the current window ID, which
+    /// raises it and gives it the input focus, unlike
+    /// [window_focus](#method.window_focus). Returns whether the request
+    /// could be sent.
+    /// This is Linux-specific.
+    pub fn activate_window(&mut self) -> bool {
+        self.activate_window_id(self.window)
+    }
+    /// Like [activate_window](#method.activate_window) for `window` instead
+    /// of the current window ID, e.g. one found by
+    /// [find_windows_by_pid](#method.find_windows_by_pid).
+    /// This is Linux-specific.
+    pub fn activate_window_id(&mut self, window: i32) -> bool {
+        self.xdo_call(|enigo| unsafe { xdo_activate_window(enigo.xdo, window as Window) })
+            .is_ok()
+    }
     /// Get the focus in current window ID
+    ///
+    /// Windows that take the focus themselves (see
+    /// [FocusModel](enum.FocusModel.html)) are sent `WM_TAKE_FOCUS` with a
+    /// timestamp of the X server, as ICCCM asks for. Returns whether the
+    /// focus could be requested.
     /// This is Linux-specific
-    pub fn window_focus(&mut self) -> i32{
-        unsafe {
-            xdo_focus_window(self.xdo, self.window)
+    pub fn window_focus(&mut self) -> bool {
+        let window = self.window as Window;
+        let model = if window == CURRENT_WINDOW {
+            FocusModel::Passive
+        } else {
+            self.window_focus_model(self.window)
+        };
+        if model == FocusModel::LocallyActive || model == FocusModel::GloballyActive {
+            let sent = match (self.intern_atom("WM_PROTOCOLS"), self.intern_atom("WM_TAKE_FOCUS")) {
+                (Some(protocols), Some(take_focus)) => {
+                    let data = [take_focus as i64, self.server_time() as i64, 0, 0, 0];
+                    self.send_client_message_to(window, window, protocols, data, xlib::NO_EVENT_MASK)
+                }
+                _ => false,
+            };
+            if model == FocusModel::GloballyActive {
+                return sent;
+            }
+        }
+        self.xdo_call(|enigo| unsafe { xdo_focus_window(enigo.xdo, window) })
+            .is_ok()
+    }
+    /// Get the ICCCM focus model of `window`, from the input hint in its
+    /// `WM_HINTS` and whether its `WM_PROTOCOLS` list `WM_TAKE_FOCUS`.
+    /// This is Linux-specific.
+    pub fn window_focus_model(&self, window: i32) -> FocusModel {
+        let window = window as Window;
+        // no input hint means the window relies on the window manager
+        let input = self.wm_input_hint(window).unwrap_or(true);
+        match (input, self.wm_takes_focus(window)) {
+            (false, false) => FocusModel::NoInput,
+            (true, false) => FocusModel::Passive,
+            (true, true) => FocusModel::LocallyActive,
+            (false, true) => FocusModel::GloballyActive,
         }
     }
+    /// Click `button` at `x`, `y` relative to `window` by sending it the
+    /// press and release events directly, instead of moving the pointer
+    /// there. This reaches windows placed offscreen, e.g. a helper window
+    /// at (-2000, -2000).
+    ///
+    /// The events are marked as sent by a client, which some applications
+    /// ignore. Returns false if the events couldn't be sent.
+    /// This is Linux-specific.
+    pub fn send_click_to_window(&self, window: i32, x: i32, y: i32, button: MouseButton) -> bool {
+        let (window_x, window_y) = match self.window_location(window) {
+            Some(location) => location,
+            None => return false,
+        };
+        let display = self.display();
+        let button = mousebutton(button) as c_uint;
+        let mut event = xlib::XEvent {
+            button: xlib::XButtonEvent {
+                type_: xlib::BUTTON_PRESS,
+                serial: 0,
+                send_event: xlib::TRUE,
+                display,
+                window: window as Window,
+                root: unsafe { xlib::XDefaultRootWindow(display) },
+                subwindow: 0,
+                time: xlib::CURRENT_TIME,
+                x,
+                y,
+                x_root: window_x + x,
+                y_root: window_y + y,
+                state: 0,
+                button,
+                same_screen: xlib::TRUE,
+            },
+        };
+        self.trapped(|| unsafe {
+            let pressed = xlib::XSendEvent(
+                display,
+                window as Window,
+                xlib::TRUE,
+                xlib::BUTTON_PRESS_MASK,
+                &mut event,
+            );
+            event.button.type_ = xlib::BUTTON_RELEASE;
+            // on release the state still has the bit of the button, which
+            // only exists for buttons 1 to 5
+            if button <= 5 {
+                event.button.state = BUTTON1_MASK << (button - 1);
+            }
+            let released = xlib::XSendEvent(
+                display,
+                window as Window,
+                xlib::TRUE,
+                xlib::BUTTON_RELEASE_MASK,
+                &mut event,
+            );
+            pressed != 0 && released != 0
+        })
+        .unwrap_or(false)
+    }
     /// Set window size
     /// This is Linux-specific
     pub fn set_window_size(&mut self, width: i32, height: i32) -> i32{
-        unsafe {
-            xdo_set_window_size(self.xdo, self.window, width, height, 0)
-        }
+        self.trapped(|| unsafe {
+            xdo_set_window_size(self.xdo, self.window as Window, width, height, 0)
+        })
+        .unwrap_or(1)
+    }
+    /// Move the current window ID so that its top left corner is at `x`,
+    /// `y` on the root window.
+    ///
+    /// Fails if libxdo or the X server rejects the request. The window
+    /// manager may still place the window elsewhere, which only shows in
+    /// [window_geometry](#method.window_geometry) afterwards.
+    /// This is Linux-specific.
+    pub fn move_window(&mut self, x: i32, y: i32) -> Result<(), EnigoError> {
+        self.move_window_id(self.window, x, y)
+    }
+    /// Like [move_window](#method.move_window) for `window` instead of the
+    /// current window ID.
+    /// This is Linux-specific.
+    pub fn move_window_id(&mut self, window: i32, x: i32, y: i32) -> Result<(), EnigoError> {
+        self.xdo_call(|enigo| unsafe { xdo_move_window(enigo.xdo, window as Window, x, y) })
+    }
+    /// Resize the current window ID to `width`x`height` pixels.
+    ///
+    /// Fails if libxdo or the X server rejects the request. The window
+    /// manager may still pick another size, e.g. for a maximized window or
+    /// to keep the size hints of the window.
+    /// This is Linux-specific.
+    pub fn resize_window(&mut self, width: u32, height: u32) -> Result<(), EnigoError> {
+        self.resize_window_id(self.window, width, height)
+    }
+    /// Like [resize_window](#method.resize_window) for `window` instead of
+    /// the current window ID.
+    /// This is Linux-specific.
+    pub fn resize_window_id(&mut self, window: i32, width: u32, height: u32) -> Result<(), EnigoError> {
+        self.xdo_call(|enigo| unsafe {
+            xdo_set_window_size(enigo.xdo, window as Window, width as c_int, height as c_int, 0)
+        })
     }
     /// Get pid of window ID
     /// This is Linux-specific
     pub fn window_pid(&mut self) -> i32 {
-        unsafe {
-            xdo_get_pid_window(self.xdo, self.window)
+        self.trapped(|| unsafe { xdo_get_pid_window(self.xdo, self.window as Window) })
+            .unwrap_or(0)
+    }
+    /// Get the title of the current window ID, e.g. to tell apart the
+    /// windows found by [find_windows_by_pid](#method.find_windows_by_pid).
+    /// Returns `None` if the window has no or an empty title.
+    /// This is Linux-specific.
+    pub fn window_name(&self) -> Option<String> {
+        self.window_name_of(self.window)
+    }
+    /// Get the position and size of `window` as x, y, width and height,
+    /// with the position relative to the root window.
+    ///
+    /// Zeros are returned for what couldn't be read, e.g. after the window
+    /// was closed: the BadWindow error of the X server is caught rather than
+    /// ending the process.
+    /// This is Linux-specific.
+    pub fn window_geometry(&self, window: i32) -> (i32, i32, i32, i32) {
+        let (x, y) = self.window_location(window).unwrap_or((0, 0));
+        let (width, height) = self.window_size(window).unwrap_or((0, 0));
+        (x, y, width as i32, height as i32)
+    }
+    /// Get the position and size of the current window, see
+    /// [window_geometry](#method.window_geometry).
+    /// This is Linux-specific.
+    pub fn window_geometry_current(&self) -> (i32, i32, i32, i32) {
+        self.window_geometry(self.window)
+    }
+    /// Get the size of the decorations the window manager puts around
+    /// `window` as left, right, top and bottom border, from the EWMH
+    /// `_NET_FRAME_EXTENTS`. Subtract left and top from a position inside
+    /// the frame to get one in the client area of
+    /// [window_geometry](#method.window_geometry).
+    ///
+    /// Zeros are returned for undecorated windows and window managers that
+    /// don't set the property.
+    /// This is Linux-specific.
+    pub fn window_frame_extents(&self, window: i32) -> (i32, i32, i32, i32) {
+        match self.property_longs(window as Window, "_NET_FRAME_EXTENTS")[..] {
+            [left, right, top, bottom, ..] => (left as i32, right as i32, top as i32, bottom as i32),
+            _ => (0, 0, 0, 0),
         }
     }
     /// Search window by pid
+    /// Returns the number of windows found, see
+    /// [find_windows_by_pid](#method.find_windows_by_pid) for their IDs.
     /// This is Linux-specific
     pub fn search_window_by_pid(&mut self, pid: i32) -> i32 {
+        self.find_windows_by_pid(pid).len() as i32
+    }
+    /// Find the IDs of all windows whose `_NET_WM_PID` is `pid`.
+    /// This is Linux-specific.
+    pub fn find_windows_by_pid(&self, pid: i32) -> Vec<i32> {
         let search = Search {
-            pid: pid as c_int,            
-            max_depth: 100 as c_long,    
-            searchmask: (1u64 << 3) as c_uint, 
+            pid: pid as c_int,
+            max_depth: 100 as c_long,
+            searchmask: SEARCH_PID,
             ..Search::default()
         };
-        let search_ptr: *const c_void = &search as *const _ as *const c_void;
-        let mut list: *mut i32 = std::ptr::null_mut();
-        let list_ptr: *mut *mut i32 = &mut list;
-        let mut count: u32 = 0;
-        let count_ptr: *mut u32 = &mut count;
-        
-        let output = unsafe {
-            xdo_search_windows(self.xdo, search_ptr, list_ptr, count_ptr);  
-            *count_ptr as u32 
-        };
-        println!("number of windows: {}", output);
-        output as i32
+        self.search_windows(&search)
     }
-}
-impl Drop for Enigo {
-    fn drop(&mut self) {
-        unsafe {
-            xdo_free(self.xdo);
+    /// Wait until a window of the process `pid` shows up, e.g. right after
+    /// launching an application, and return the first one found.
+    ///
+    /// Returns `None` once `timeout` passed without a window, or when the
+    /// [cancel flag](#method.set_cancel_flag) is set.
+    /// This is Linux-specific.
+    pub fn wait_for_window_by_pid(&mut self, pid: i32, timeout: Duration) -> Option<i32> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(&window) = self.find_windows_by_pid(pid).first() {
+                return Some(window);
+            }
+            let now = Instant::now();
+            if now >= deadline || self.cancelled() {
+                return None;
+            }
+            thread::sleep(WINDOW_POLL.min(deadline - now));
         }
     }
-}
-impl MouseControllable for Enigo {
-    fn mouse_move_to(&mut self, x: i32, y: i32) {
-        unsafe {
-            xdo_move_mouse(self.xdo, x as c_int, y as c_int, 0);
+    /// Get the ID of the window that currently has the input focus.
+    ///
+    /// Returns `None` if no window is focused.
+    /// This is Linux-specific.
+    pub fn focused_window(&self) -> Option<i32> {
+        let mut window: Window = 0;
+        // the focused window may be closed while libxdo walks up to its frame
+        let ret = self.trapped(|| unsafe { xdo_get_focused_window_sane(self.xdo, &mut window) });
+        if ret == Some(0) && window != 0 {
+            Some(window as i32)
+        } else {
+            None
         }
     }
-    fn mouse_move_relative(&mut self, x: i32, y: i32) {
-        unsafe {
-            xdo_move_mouse_relative(self.xdo, x as c_int, y as c_int);
+    /// Click `button` at `x` and `y` relative to the top-left corner of the
+    /// focused window.
+    ///
+    /// The offsets are scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    /// Nothing happens if no window is focused.
+    /// This is Linux-specific.
+    pub fn click_in_focused(&mut self, x: i32, y: i32, button: MouseButton) {
+        self.step(|enigo| {
+            let location = enigo
+                .focused_window()
+                .and_then(|window| enigo.window_location(window));
+            if let Some((window_x, window_y)) = location {
+                let (x, y) = enigo.scaled(x, y);
+                enigo.move_pointer(window_x + x, window_y + y);
+                enigo.mouse_click(button);
+            }
+        })
+    }
+    /// Check whether `window` can receive keyboard input at all.
+    ///
+    /// The window has to be viewable and must either accept input through
+    /// its `WM_HINTS` or take the focus itself via `WM_TAKE_FOCUS`.
+    /// This is Linux-specific.
+    pub fn window_accepts_input(&self, window: i32) -> bool {
+        let window = window as Window;
+        if !self.is_mapped_viewable(window) {
+            return false;
         }
+        self.wm_input_hint(window).unwrap_or(true) || self.wm_takes_focus(window)
     }
-    fn mouse_down(&mut self, button: MouseButton) {
-        unsafe {
-            xdo_mouse_down(self.xdo, self.window, mousebutton(button));
+    /// Check whether `window` is actually visible: mapped, with all its
+    /// parents mapped, and on the current desktop.
+    ///
+    /// Window managers that keep windows of other desktops mapped are
+    /// covered by comparing the EWMH `_NET_WM_DESKTOP` of the window with
+    /// `_NET_CURRENT_DESKTOP`. Windows on all desktops count as on the
+    /// current one.
+    /// This is Linux-specific.
+    pub fn window_is_viewable(&self, window: i32) -> bool {
+        let window = window as Window;
+        if !self.is_mapped_viewable(window) {
+            return false;
+        }
+        let root = unsafe { xlib::XDefaultRootWindow(self.display()) };
+        let current = self.property_longs(root, "_NET_CURRENT_DESKTOP");
+        match (self.property_longs(window, "_NET_WM_DESKTOP").first(), current.first()) {
+            (Some(&desktop), Some(&current)) => desktop as u32 == ALL_DESKTOPS || desktop == current,
+            _ => true,
         }
     }
-    fn mouse_up(&mut self, button: MouseButton) {
-        unsafe {
-            xdo_mouse_up(self.xdo, self.window, mousebutton(button));
+    /// Send a `ClientMessage` of type `message_type` about `window` to the
+    /// root window, the way EWMH requests like `_NET_WM_STATE` are sent to
+    /// the window manager.
+    ///
+    /// The message type atom is interned by name and `data` is sent in the
+    /// 32 bit format. Returns whether the event was sent, which it isn't for
+    /// a message type with a NUL byte.
+    /// This is Linux-specific.
+    pub fn send_client_message(&mut self, window: i32, message_type: &str, data: [i64; 5]) -> bool {
+        let root = unsafe { xlib::XDefaultRootWindow(self.display()) };
+        let message_type = match self.intern_atom(message_type) {
+            Some(atom) => atom,
+            None => return false,
+        };
+        self.send_client_message_to(
+            root,
+            window as Window,
+            message_type,
+            data,
+            xlib::SUBSTRUCTURE_REDIRECT_MASK | xlib::SUBSTRUCTURE_NOTIFY_MASK,
+        )
+    }
+    /// Gather the title, class, role, PID, geometry and state of `window` into
+    /// one snapshot, e.g. to print it when a window match goes wrong.
+    /// This is Linux-specific.
+    pub fn window_info(&self, window: i32) -> WindowInfo {
+        let id = window;
+        let window = window as Window;
+        let display = self.display();
+        let (class_name, class) = self
+            .trapped(|| unsafe {
+                let mut hint = xlib::XClassHint {
+                    res_name: ptr::null_mut(),
+                    res_class: ptr::null_mut(),
+                };
+                if xlib::XGetClassHint(display, window, &mut hint) != 0 {
+                    (take_x_string(hint.res_name), take_x_string(hint.res_class))
+                } else {
+                    (None, None)
+                }
+            })
+            .unwrap_or((None, None));
+        let viewable = self.is_mapped_viewable(window);
+        let (x, y) = self.window_location(id).unwrap_or((0, 0));
+        let (width, height) = self.window_size(id).unwrap_or((0, 0));
+        WindowInfo {
+            id,
+            name: self.window_name_of(id),
+            class,
+            class_name,
+            role: self.property_string(window, "WM_WINDOW_ROLE"),
+            pid: self.trapped(|| unsafe { xdo_get_pid_window(self.xdo, window) }).unwrap_or(0),
+            geometry: (x, y, width, height),
+            viewable,
+            state: self
+                .property_longs(window, "_NET_WM_STATE")
+                .into_iter()
+                .filter_map(|atom| self.atom_name(atom as xlib::Atom))
+                .collect(),
         }
     }
-    fn mouse_click(&mut self, button: MouseButton) {
-        unsafe {
-            xdo_click_window(self.xdo, self.window, mousebutton(button));
+    /// Paste `text` the way many terminals expect it: put it into the primary
+    /// selection and press Shift+Insert.
+    ///
+    /// The selection is served from a background thread until another client
+    /// takes it over, so keep the process alive until the text got pasted.
+    /// This is Linux-specific.
+    pub fn paste_shift_insert(&mut self, text: &str) {
+        self.step(|enigo| {
+            if selection::serve(enigo.display_name(), "PRIMARY", text.to_string()) {
+                let _ = enigo.try_key_combo(&[Key::Shift], Key::Insert);
+            }
+        })
+    }
+    /// List the names of the properties set on `window`, e.g. to see whether
+    /// it has `_NET_WM_NAME`, `WM_NAME` or neither.
+    /// This is Linux-specific.
+    pub fn window_properties(&self, window: i32) -> Vec<String> {
+        let mut count = 0;
+        let atoms = self
+            .trapped(|| unsafe {
+                let list = xlib::XListProperties(self.display(), window as Window, &mut count);
+                if list.is_null() {
+                    return Vec::new();
+                }
+                let atoms = std::slice::from_raw_parts(list, count as usize).to_vec();
+                xlib::XFree(list as *mut c_void);
+                atoms
+            })
+            .unwrap_or_default();
+        atoms.into_iter().filter_map(|atom| self.atom_name(atom)).collect()
+    }
+    /// The operations the window manager allows on `window`, read from
+    /// `_NET_WM_ALLOWED_ACTIONS`, e.g. to skip resizing a fixed-size dialog.
+    /// Actions unknown to enigo are left out, and the list is empty if the
+    /// window manager doesn't set the property.
+    /// This is Linux-specific.
+    pub fn window_allowed_actions(&self, window: i32) -> Vec<WindowAction> {
+        self.property_longs(window as Window, "_NET_WM_ALLOWED_ACTIONS")
+            .into_iter()
+            .filter_map(|atom| self.atom_name(atom as xlib::Atom))
+            .filter_map(|name| WindowAction::from_atom_name(&name))
+            .collect()
+    }
+    /// Drag `data` of type `mime` (e.g. `text/plain` or `text/uri-list`)
+    /// from `source` and drop it on `target` at `position`, through the XDND
+    /// protocol that toolkit applications need for a real drag-and-drop.
+    ///
+    /// The pointer moves from the center of `source` to `position`, while
+    /// the data is offered by a helper window standing in for `source`,
+    /// because only the owner of `XdndSelection` can receive the replies of
+    /// the target. `target` has to be the top-level window advertising
+    /// `XdndAware`. The position is scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    /// Returns an error if the target doesn't take the drop, or
+    /// `InvalidInput` for a type with a NUL byte.
+    /// This is Linux-specific.
+    pub fn xdnd_drag(
+        &mut self,
+        source: i32,
+        target: i32,
+        position: (i32, i32),
+        data: &str,
+        mime: &str,
+    ) -> Result<(), EnigoError> {
+        if mime.contains('\0') {
+            return Err(EnigoError::InvalidInput(mime.to_string()));
         }
+        self.step(|enigo| {
+            enigo.handle_errors(|enigo| {
+                let (x, y) = enigo.scaled(position.0, position.1);
+                if let (Some((left, top)), Some((width, height))) =
+                    (enigo.window_location(source), enigo.window_size(source))
+                {
+                    enigo.stroke_raw(
+                        &[(left + (width / 2) as i32, top + (height / 2) as i32), (x, y)],
+                        Duration::from_millis(100),
+                    );
+                } else {
+                    enigo.move_pointer(x, y);
+                }
+                let display_name = enigo.display_name();
+                if xdnd::drop_on(display_name, target as Window, x, y, data.as_bytes(), mime) {
+                    Ok(())
+                } else {
+                    Err(EnigoError::DropRejected(target))
+                }
+            })
+        })
     }
-    fn mouse_scroll_x(&mut self, length: i32) {
-        let button;
-        let mut length = length;
-
-        if length < 0 {
-            button = MouseButton::ScrollLeft;
-        } else {
-            button = MouseButton::ScrollRight;
+    /// Capture the attributes of `window` that stay the same when its
+    /// application is restarted, to find the new window later with
+    /// [resolve_identity](#method.resolve_identity).
+    ///
+    /// The title pattern is left out, because titles tend to change with
+    /// the document shown. Set it to tell apart windows of the same class.
+    /// This is Linux-specific.
+    pub fn window_identity(&self, window: i32) -> WindowIdentity {
+        let info = self.window_info(window);
+        WindowIdentity {
+            class: info.class,
+            class_name: info.class_name,
+            role: info.role,
+            title_pattern: None,
+            process_name: process_name(info.pid),
         }
+    }
+    /// Find the current window with the attributes of `identity`, e.g. after
+    /// its application was restarted and the old window ID became invalid.
+    ///
+    /// Returns `None` if no window matches, or if `identity` has neither
+    /// class nor role nor title pattern to search for.
+    /// This is Linux-specific.
+    pub fn resolve_identity(&mut self, identity: &WindowIdentity) -> Option<i32> {
+        let pattern = |text: &Option<String>, exact: bool| match text {
+            Some(text) if exact => CString::new(format!("^{}$", escape_regex(text))).map(Some),
+            Some(text) => CString::new(text.as_str()).map(Some),
+            None => Ok(None),
+        };
+        let class = pattern(&identity.class, true).ok()?;
+        let class_name = pattern(&identity.class_name, true).ok()?;
+        let role = pattern(&identity.role, true).ok()?;
+        let title = pattern(&identity.title_pattern, false).ok()?;
+        let mut search = Search {
+            max_depth: 100 as c_long,
+            require: SEARCH_ALL,
+            ..Search::default()
+        };
+        for (pattern, field, mask) in [
+            (&class, &mut search.winclass, SEARCH_CLASS),
+            (&class_name, &mut search.winclassname, SEARCH_CLASSNAME),
+            (&role, &mut search.winrole, SEARCH_ROLE),
+            (&title, &mut search.winname, SEARCH_NAME),
+        ] {
+            if let Some(pattern) = pattern {
+                *field = pattern.as_ptr();
+                search.searchmask |= mask;
+            }
+        }
+        if search.searchmask == 0 {
+            return None;
+        }
+        self.search_windows(&search).into_iter().find(|&window| {
+            identity.process_name.is_none()
+                || self
+                    .trapped(|| unsafe { xdo_get_pid_window(self.xdo, window as Window) })
+                    .and_then(process_name)
+                    == identity.process_name
+        })
+    }
+    /// Find the windows whose name is exactly `title`.
+    ///
+    /// libxdo matches names against a case-insensitive regular expression,
+    /// so the title is escaped and anchored before searching and the results
+    /// are then compared literally against the window name.
+    ///
+    /// Nothing is found for a title with a NUL byte.
+    /// This is Linux-specific.
+    pub fn find_window_exact(&mut self, title: &str) -> Vec<i32> {
+        let pattern = match CString::new(format!("^{}$", escape_regex(title))) {
+            Ok(pattern) => pattern,
+            Err(_) => return Vec::new(),
+        };
+        let search = Search {
+            winname: pattern.as_ptr(),
+            max_depth: 100 as c_long,
+            searchmask: SEARCH_NAME,
+            ..Search::default()
+        };
+        self.search_windows(&search)
+            .into_iter()
+            .filter(|&window| self.window_name_of(window).as_deref() == Some(title))
+            .collect()
+    }
+    /// Find the windows with a title matching `pattern`, a case-insensitive
+    /// regular expression like in `xdotool search --name`.
+    /// This is Linux-specific.
+    pub fn find_windows_by_name(&self, pattern: &str) -> Vec<i32> {
+        self.find_windows(SEARCH_NAME, pattern)
+    }
+    /// Find the windows with a class or class name in `WM_CLASS` matching
+    /// `pattern`, a case-insensitive regular expression like in
+    /// `xdotool search --class --classname`.
+    /// This is Linux-specific.
+    pub fn find_windows_by_class(&self, pattern: &str) -> Vec<i32> {
+        self.find_windows(SEARCH_CLASS | SEARCH_CLASSNAME, pattern)
+    }
+    /// Find the windows where any of the attributes selected by `mask`
+    /// matches `pattern`, a case-insensitive regular expression. `mask`
+    /// combines `SEARCH_NAME`, `SEARCH_CLASS`, `SEARCH_CLASSNAME`,
+    /// `SEARCH_ROLE` and `SEARCH_TITLE`; other bits are ignored.
+    ///
+    /// Nothing is found for a pattern with a NUL byte.
+    /// This is Linux-specific.
+    pub fn find_windows(&self, mask: u32, pattern: &str) -> Vec<i32> {
+        let pattern = match CString::new(pattern) {
+            Ok(pattern) => pattern,
+            Err(_) => return Vec::new(),
+        };
+        let mut search = Search {
+            max_depth: 100 as c_long,
+            ..Search::default()
+        };
+        for (field, bit) in [
+            (&mut search.title, SEARCH_TITLE),
+            (&mut search.winclass, SEARCH_CLASS),
+            (&mut search.winclassname, SEARCH_CLASSNAME),
+            (&mut search.winrole, SEARCH_ROLE),
+            (&mut search.winname, SEARCH_NAME),
+        ] {
+            if mask & bit != 0 {
+                *field = pattern.as_ptr();
+                search.searchmask |= bit;
+            }
+        }
+        if search.searchmask == 0 {
+            return Vec::new();
+        }
+        self.search_windows(&search)
+    }
+    fn search_windows(&self, search: &Search) -> Vec<i32> {
+        let search_ptr: *const c_void = search as *const _ as *const c_void;
+        let mut list: *mut Window = ptr::null_mut();
+        let mut count: c_uint = 0;
+        // windows closed during the search only drop out of the result
+        xerror::trap(self.display(), || unsafe {
+            xdo_search_windows(self.xdo, search_ptr, &mut list, &mut count);
+        });
+        unsafe {
+            if list.is_null() {
+                return Vec::new();
+            }
+            let windows = std::slice::from_raw_parts(list, count as usize)
+                .iter()
+                .map(|&window| window as i32)
+                .collect();
+            libc::free(list as *mut c_void);
+            windows
+        }
+    }
+    // The input field of WM_HINTS, if the window sets it
+    fn wm_input_hint(&self, window: Window) -> Option<bool> {
+        self.trapped(|| unsafe {
+            let hints = xlib::XGetWMHints(self.display(), window);
+            if hints.is_null() {
+                return None;
+            }
+            let input = if (*hints).flags & xlib::INPUT_HINT != 0 {
+                Some((*hints).input != xlib::FALSE)
+            } else {
+                None
+            };
+            xlib::XFree(hints as *mut c_void);
+            input
+        })
+        .flatten()
+    }
+    // Whether WM_PROTOCOLS lists WM_TAKE_FOCUS
+    fn wm_takes_focus(&self, window: Window) -> bool {
+        let display = self.display();
+        let take_focus = match self.intern_atom("WM_TAKE_FOCUS") {
+            Some(atom) => atom,
+            None => return false,
+        };
+        let (mut protocols, mut count) = (ptr::null_mut(), 0);
+        self.trapped(|| unsafe {
+            if xlib::XGetWMProtocols(display, window, &mut protocols, &mut count) == 0
+                || protocols.is_null()
+            {
+                return false;
+            }
+            let found = std::slice::from_raw_parts(protocols, count as usize).contains(&take_focus);
+            xlib::XFree(protocols as *mut c_void);
+            found
+        })
+        .unwrap_or(false)
+    }
+    fn send_client_message_to(
+        &self,
+        destination: Window,
+        window: Window,
+        message_type: xlib::Atom,
+        data: [i64; 5],
+        event_mask: c_long,
+    ) -> bool {
+        let display = self.display();
+        let mut event = xlib::XEvent {
+            client_message: xlib::XClientMessageEvent {
+                type_: xlib::CLIENT_MESSAGE,
+                serial: 0,
+                send_event: 1,
+                display,
+                window,
+                message_type,
+                format: 32,
+                data: [
+                    data[0] as c_long,
+                    data[1] as c_long,
+                    data[2] as c_long,
+                    data[3] as c_long,
+                    data[4] as c_long,
+                ],
+            },
+        };
+        self.trapped(|| unsafe {
+            xlib::XSendEvent(display, destination, xlib::FALSE, event_mask, &mut event) != 0
+        })
+        .unwrap_or(false)
+    }
+    // The current time of the X server, from the PropertyNotify event of an
+    // empty append to a property of a hidden window
+    fn server_time(&self) -> xlib::Time {
+        let display = self.display();
+        let property = match self.intern_atom("ENIGO_TIMESTAMP") {
+            Some(atom) => atom,
+            None => return xlib::CURRENT_TIME,
+        };
+        unsafe {
+            let root = xlib::XDefaultRootWindow(display);
+            let window = xlib::XCreateSimpleWindow(display, root, 0, 0, 1, 1, 0, 0, 0);
+            xlib::XSelectInput(display, window, xlib::PROPERTY_CHANGE_MASK);
+            xlib::XChangeProperty(
+                display,
+                window,
+                property,
+                xlib::XA_ATOM,
+                32,
+                xlib::PROP_MODE_APPEND,
+                ptr::null(),
+                0,
+            );
+            let mut event: xlib::XEvent = std::mem::zeroed();
+            xlib::XWindowEvent(display, window, xlib::PROPERTY_CHANGE_MASK, &mut event);
+            xlib::XDestroyWindow(display, window);
+            event.property.time
+        }
+    }
+    // The atom named `name`, or `None` for a name with a NUL byte
+    fn intern_atom(&self, name: &str) -> Option<xlib::Atom> {
+        let name = CString::new(name).ok()?;
+        Some(unsafe { xlib::XInternAtom(self.display(), name.as_ptr(), xlib::FALSE) })
+    }
+    fn window_location(&self, window: i32) -> Option<(i32, i32)> {
+        let (mut x, mut y) = (0, 0);
+        let ret = self.trapped(|| unsafe {
+            xdo_get_window_location(self.xdo, window as Window, &mut x, &mut y, ptr::null_mut())
+        });
+        if ret == Some(0) {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+    fn window_size(&self, window: i32) -> Option<(u32, u32)> {
+        let (mut width, mut height) = (0, 0);
+        let ret = self.trapped(|| unsafe {
+            xdo_get_window_size(self.xdo, window as Window, &mut width, &mut height)
+        });
+        if ret == Some(0) {
+            Some((width, height))
+        } else {
+            None
+        }
+    }
+    // Read a window property and hand its format, data and item count to `read`
+    fn window_property<T>(
+        &self,
+        window: Window,
+        property: &str,
+        read: impl FnOnce(c_int, *const c_uchar, usize) -> T,
+    ) -> Option<T> {
+        let property = self.intern_atom(property)?;
+        let (mut actual_type, mut format, mut nitems, mut bytes_after) = (0, 0, 0, 0);
+        let mut data: *mut c_uchar = ptr::null_mut();
+        self.trapped(|| unsafe {
+            let status = xlib::XGetWindowProperty(
+                self.display(),
+                window,
+                property,
+                0,
+                c_long::MAX,
+                xlib::FALSE,
+                xlib::ANY_PROPERTY_TYPE,
+                &mut actual_type,
+                &mut format,
+                &mut nitems,
+                &mut bytes_after,
+                &mut data,
+            );
+            if status != xlib::SUCCESS || data.is_null() {
+                return None;
+            }
+            let result = read(format, data, nitems as usize);
+            xlib::XFree(data as *mut c_void);
+            Some(result)
+        })
+        .flatten()
+    }
+    // An 8 bit property like STRING or UTF8_STRING
+    fn property_string(&self, window: Window, property: &str) -> Option<String> {
+        self.window_property(window, property, |format, data, nitems| {
+            if format != 8 || nitems == 0 {
+                return None;
+            }
+            let bytes = unsafe { std::slice::from_raw_parts(data, nitems) };
+            Some(String::from_utf8_lossy(bytes).into_owned())
+        })
+        .and_then(|string| string)
+    }
+    // A 32 bit property like CARDINAL[] or ATOM[], which Xlib hands out as longs
+    fn property_longs(&self, window: Window, property: &str) -> Vec<c_long> {
+        self.window_property(window, property, |format, data, nitems| {
+            if format != 32 {
+                return Vec::new();
+            }
+            unsafe { std::slice::from_raw_parts(data as *const c_long, nitems) }.to_vec()
+        })
+        .unwrap_or_default()
+    }
+    fn window_name_of(&self, window: i32) -> Option<String> {
+        let mut name: *mut c_uchar = ptr::null_mut();
+        let (mut len, mut name_type) = (0, 0);
+        self.trapped(|| unsafe {
+            xdo_get_window_name(self.xdo, window as Window, &mut name, &mut len, &mut name_type);
+            if name.is_null() {
+                return None;
+            }
+            let bytes = std::slice::from_raw_parts(name, len as usize);
+            let string = String::from_utf8_lossy(bytes).into_owned();
+            xlib::XFree(name as *mut c_void);
+            if string.is_empty() {
+                None
+            } else {
+                Some(string)
+            }
+        })
+        .flatten()
+    }
+    // Whether `window` and all its parents are mapped
+    fn is_mapped_viewable(&self, window: Window) -> bool {
+        self.trapped(|| unsafe {
+            let mut attributes: xlib::XWindowAttributes = std::mem::zeroed();
+            xlib::XGetWindowAttributes(self.display(), window, &mut attributes) != 0
+                && attributes.map_state == xlib::IS_VIEWABLE
+        })
+        .unwrap_or(false)
+    }
+    // Run the Xlib or xdo requests of `call` on a window that may be gone,
+    // `None` if they failed with an X error like BadWindow
+    fn trapped<T>(&self, call: impl FnOnce() -> T) -> Option<T> {
+        match xerror::trap(self.display(), call) {
+            (result, None) => Some(result),
+            (_, Some(_)) => None,
+        }
+    }
+}
+// The XKB symbols component for a list of layouts, the way setxkbmap builds it
+// from the evdev rules: "us,de" becomes "pc+us+de:2+inet(evdev)"
+fn keymap_symbols(layouts: &str) -> Option<String> {
+    let mut symbols = String::from("pc");
+    for (i, layout) in layouts.split(',').map(str::trim).enumerate() {
+        if layout.is_empty() || layout.contains(|c: char| c == '+' || c == ':' || c.is_whitespace()) {
+            return None;
+        }
+        symbols.push('+');
+        symbols.push_str(layout);
+        if i > 0 {
+            symbols.push_str(&format!(":{}", i + 1));
+        }
+    }
+    symbols.push_str("+inet(evdev)");
+    Some(symbols)
+}
+#[allow(deprecated)]
+fn is_modifier(key: Key) -> bool {
+    matches!(
+        key,
+        Key::Alt
+            | Key::Command
+            | Key::Control
+            | Key::Meta
+            | Key::ModeSwitch
+            | Key::Option
+            | Key::Shift
+            | Key::Super
+            | Key::Windows
+    )
+}
+// Split `text` into pieces of at most `size` characters
+fn text_chunks(text: &str, size: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let end = rest.char_indices().nth(size).map_or(rest.len(), |(i, _)| i);
+        chunks.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    chunks
+}
+// The keysym of `c`: Latin-1 characters are their own keysym, the rest of
+// Unicode is offset by 0x01000000
+fn char_keysym(c: char) -> xlib::KeySym {
+    match c as u32 {
+        code @ 0x20..=0x7e | code @ 0xa0..=0xff => xlib::KeySym::from(code),
+        code => 0x0100_0000 | xlib::KeySym::from(code),
+    }
+}
+// Split text at tabs and line breaks into the text before each of them and its key
+fn split_controls(text: &str) -> Vec<(&str, Option<Key>)> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let key = match c {
+            '\t' => Key::Tab,
+            '\r' | '\n' => Key::Return,
+            _ => continue,
+        };
+        pieces.push((&text[start..i], Some(key)));
+        start = i + 1;
+        if c == '\r' && chars.peek().map(|&(_, next)| next) == Some('\n') {
+            chars.next();
+            start += 1;
+        }
+    }
+    if start < text.len() {
+        pieces.push((&text[start..], None));
+    }
+    pieces
+}
+// The keys typing `text`, each mapped by `lookup`, with Tab for `\t` and
+// Return for every line break, or the first character `lookup` has no
+// mapping for
+fn text_keys<T>(text: &str, mut lookup: impl FnMut(Key) -> Option<T>) -> Result<Vec<T>, char> {
+    let mut keys = Vec::new();
+    for (i, line) in split_lines(text).into_iter().enumerate() {
+        if i > 0 {
+            keys.push(lookup(Key::Return).ok_or('\n')?);
+        }
+        for c in line.chars() {
+            let key = if c == '\t' { Key::Tab } else { Key::Layout(c) };
+            keys.push(lookup(key).ok_or(c)?);
+        }
+    }
+    Ok(keys)
+}
+// The lines of `text`, ended by `\n`, `\r\n` or a lone `\r`
+fn split_lines(text: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '\r' && c != '\n' {
+            continue;
+        }
+        lines.push(&text[start..i]);
+        start = i + 1;
+        if c == '\r' && chars.peek().map(|&(_, next)| next) == Some('\n') {
+            chars.next();
+            start += 1;
+        }
+    }
+    lines.push(&text[start..]);
+    lines
+}
+// The union of the monitor rectangles as left, top, right and bottom edge
+fn bounding_box(monitors: &[Monitor]) -> Option<(i32, i32, i32, i32)> {
+    monitors.iter().fold(None, |bounds, monitor| {
+        let right = monitor.x + monitor.width as i32;
+        let bottom = monitor.y + monitor.height as i32;
+        Some(match bounds {
+            None => (monitor.x, monitor.y, right, bottom),
+            Some((left, top, r, b)) => (
+                left.min(monitor.x),
+                top.min(monitor.y),
+                r.max(right),
+                b.max(bottom),
+            ),
+        })
+    })
+}
+// The Unicode keysym names (`U1F1FA`) of the characters of `text`
+fn unicode_keysequence(text: &str) -> String {
+    text.chars()
+        .map(|c| format!("U{:04X}", u32::from(c)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+// The name of process `pid`, if it is known and still running
+fn process_name(pid: i32) -> Option<String> {
+    if pid <= 0 {
+        return None;
+    }
+    let comm = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    Some(comm.trim_end().to_string())
+}
+// Copy a string allocated by Xlib and free it
+unsafe fn take_x_string(string: *mut c_char) -> Option<String> {
+    if string.is_null() {
+        return None;
+    }
+    let copy = CStr::from_ptr(string).to_string_lossy().into_owned();
+    xlib::XFree(string as *mut c_void);
+    Some(copy)
+}
+// Fill in straight lines between the points so that consecutive positions are
+// at most `step` pixels apart
+fn interpolate(points: &[(i32, i32)], step: f64) -> Vec<(i32, i32)> {
+    let mut path = Vec::new();
+    let mut last = match points.first() {
+        Some(&first) => first,
+        None => return path,
+    };
+    path.push(last);
+    for &(x, y) in &points[1..] {
+        let (dx, dy) = (f64::from(x - last.0), f64::from(y - last.1));
+        let steps = (dx.hypot(dy) / step).ceil().max(1.0) as i32;
+        for i in 1..=steps {
+            let t = f64::from(i) / f64::from(steps);
+            path.push((
+                last.0 + (dx * t).round() as i32,
+                last.1 + (dy * t).round() as i32,
+            ));
+        }
+        last = (x, y);
+    }
+    path
+}
+// `delay` at `speed` times the normal speed
+fn scaled_delay(delay: Duration, speed: f64) -> Duration {
+    if speed > 0.0 {
+        Duration::from_secs_f64(delay.as_secs_f64() / speed)
+    } else {
+        Duration::from_secs(0)
+    }
+}
+// A small xorshift generator, so that fuzz runs can be replayed from their
+// seed without depending on a random number crate
+struct FuzzRng(u64);
+
+impl FuzzRng {
+    fn new(seed: u64) -> Self {
+        // xorshift gets stuck at zero
+        FuzzRng((seed ^ 0x9e37_79b9_7f4a_7c15).max(1))
+    }
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+    // A number in 0..n
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+// Whether the fuzzer must not press `key` while holding `modifiers`, because
+// it'd kill the X server, switch the virtual terminal or close a window
+fn fuzz_blocked(modifiers: &[Key], key: Key) -> bool {
+    let held = |modifier| modifiers.contains(&modifier);
+    let function_key = matches!(
+        key,
+        Key::F1 | Key::F2 | Key::F3 | Key::F4 | Key::F5 | Key::F6
+            | Key::F7 | Key::F8 | Key::F9 | Key::F10 | Key::F11 | Key::F12
+    );
+    let zap_or_switch = function_key || key == Key::Backspace || key == Key::Delete;
+    (held(Key::Control) && held(Key::Alt) && zap_or_switch)
+        || (held(Key::Alt) && (key == Key::F4 || key == Key::Tab))
+}
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.[](){}*+?|^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+impl Enigo {
+    /// Capture the pointer position, held mouse buttons and modifier/lock
+    /// state so it can be reinstated later with
+    /// [restore_state](struct.Enigo.html#method.restore_state).
+    /// This is Linux-specific.
+    pub fn snapshot_state(&self) -> InputState {
+        let (mut x, mut y, mut screen) = (0, 0, 0);
+        let mask = unsafe {
+            xdo_get_mouse_location(self.xdo, &mut x, &mut y, &mut screen);
+            xdo_get_input_state(self.xdo)
+        };
+        InputState {
+            x,
+            y,
+            buttons: mask & BUTTON_MASKS,
+            modifiers: mask & MODIFIER_MASKS,
+            locks: mask & LOCK_MASKS,
+        }
+    }
+    /// Bring the pointer, mouse buttons and modifier/lock keys back to a
+    /// state captured by
+    /// [snapshot_state](struct.Enigo.html#method.snapshot_state).
+    ///
+    /// Only buttons and keys that differ from the snapshot are pressed or
+    /// released; locks are toggled if their state differs.
+    /// This is Linux-specific.
+    pub fn restore_state(&mut self, state: &InputState) {
+        self.step(|enigo| {
+            let current = enigo.snapshot_state();
+
+            enigo.move_pointer(state.x, state.y);
+
+            let buttons = [
+                (BUTTON1_MASK, MouseButton::Left),
+                (BUTTON2_MASK, MouseButton::Middle),
+                (BUTTON3_MASK, MouseButton::Right),
+            ];
+            for &(mask, button) in buttons.iter() {
+                let wanted = state.buttons & mask != 0;
+                if wanted != (current.buttons & mask != 0) {
+                    if wanted {
+                        enigo.mouse_down(button);
+                    } else {
+                        enigo.mouse_up(button);
+                    }
+                }
+            }
+
+            let modifiers = [
+                (SHIFT_MASK, Key::Shift),
+                (CONTROL_MASK, Key::Control),
+                (MOD1_MASK, Key::Alt),
+                (MOD4_MASK, Key::Meta),
+            ];
+            for &(mask, key) in modifiers.iter() {
+                let wanted = state.modifiers & mask != 0;
+                if wanted != (current.modifiers & mask != 0) {
+                    if wanted {
+                        enigo.key_down(key);
+                    } else {
+                        enigo.key_up(key);
+                    }
+                }
+            }
+
+            let locks = [(LOCK_MASK, Key::CapsLock), (MOD2_MASK, Key::NumLock)];
+            for &(mask, key) in locks.iter() {
+                if state.locks & mask != current.locks & mask {
+                    let _ = enigo.try_key_click(key);
+                }
+            }
+        })
+    }
+
+    /// Get the keys pressed through
+    /// [key_down](trait.KeyboardControllable.html#tymethod.key_down) and not
+    /// released yet, in the order they were pressed.
+    ///
+    /// Keys held by other clients or by the user aren't included, see
+    /// [snapshot_state](#method.snapshot_state) for the modifiers held on
+    /// the display.
+    /// This is Linux-specific.
+    pub fn held_keys(&self) -> Vec<Key> {
+        self.held.clone()
+    }
+    /// Release Shift, Control, Alt, Super and AltGr on both sides of the
+    /// keyboard and turn Caps Lock off, e.g. to recover from automation
+    /// that got interrupted while holding a modifier.
+    /// This is Linux-specific.
+    pub fn release_all_modifiers(&mut self) {
+        self.step(|enigo| {
+            let modifiers: Vec<Key> = enigo.held.iter().copied().filter(|&key| is_modifier(key)).collect();
+            for key in modifiers {
+                let _ = enigo.key_event(key, false);
+            }
+            if enigo.virtual_input.is_some() {
+                // the uinput device only ever presses the left modifiers
+                for &key in &[Key::Shift, Key::Control, Key::Alt, Key::Meta] {
+                    let _ = enigo.key_event(key, false);
+                }
+            } else {
+                // through XTest: modifiers sent to a window with XSendEvent
+                // wouldn't release what the server holds
+                for keysym in MODIFIER_KEYSYMS {
+                    let keysym = CString::new(*keysym).unwrap();
+                    let _ = enigo.xdo_call(|enigo| unsafe {
+                        xdo_send_keysequence_window_up(enigo.xdo, CURRENT_WINDOW, keysym.as_ptr(), 0)
+                    });
+                }
+            }
+            if unsafe { xdo_get_input_state(enigo.xdo) } & LOCK_MASK != 0 {
+                enigo.key_click(Key::CapsLock);
+            }
+        })
+    }
+
+    /// Click `key` only if the currently held modifiers satisfy `condition`.
+    ///
+    /// Returns whether the key was clicked.
+    /// This is Linux-specific.
+    pub fn key_click_if(&mut self, key: Key, condition: ModifierCondition) -> bool {
+        let fire = condition.is_met(unsafe { xdo_get_input_state(self.xdo) });
+        if fire {
+            self.key_click(key);
+        }
+        fire
+    }
+
+    /// Type `text` and check the result through `read_back`, which should
+    /// return the current content of the target field.
+    ///
+    /// Whenever the read back content is a prefix of `text`, the missing tail
+    /// is typed again, up to a few times. Returns whether the field ends up
+    /// matching `text`. If the content can't be read or isn't a prefix of
+    /// `text` anymore, nothing is retyped.
+    /// This is Linux-specific.
+    pub fn type_and_verify(&mut self, text: &str, read_back: impl Fn() -> Option<String>) -> bool {
+        self.step(|enigo| {
+            enigo.key_sequence(text);
+            for retry in 0..VERIFY_RETRIES {
+                if enigo.cancelled() {
+                    return false;
+                }
+                match read_back() {
+                    Some(ref current) if current == text => {
+                        if retry == 0 {
+                            enigo.typed_without_drops();
+                        }
+                        return true;
+                    }
+                    Some(ref current) if text.starts_with(current.as_str()) => {
+                        if enigo.adaptive_typing {
+                            enigo.typing_delay = enigo.typing_schedule.slowed(enigo.typing_delay);
+                            enigo.clean_typings = 0;
+                        }
+                        enigo.key_sequence(&text[current.len()..]);
+                    }
+                    _ => return false,
+                }
+            }
+            read_back().as_deref() == Some(text)
+        })
+    }
+    /// Click the key in a physical position, whatever character the current
+    /// layout puts there. Useful for WASD style controls.
+    ///
+    /// Does nothing if the keyboard has no key in that position.
+    /// This is Linux-specific.
+    pub fn key_click_virtual(&mut self, vk: VirtualKey) {
+        let _ = self.try_key_click_virtual(vk);
+    }
+    /// Like [key_click_virtual](#method.key_click_virtual), but fails with
+    /// `UnknownKey` if the keyboard has no key in that position and with
+    /// `XdoFailed` if libxdo couldn't send the key.
+    /// This is Linux-specific.
+    pub fn try_key_click_virtual(&mut self, vk: VirtualKey) -> Result<(), EnigoError> {
+        self.step(|enigo| {
+            let keycode = enigo
+                .keycode_of(vk)
+                .ok_or_else(|| EnigoError::UnknownKey(format!("{:?}", vk)))?;
+            // xdo takes a number that isn't a keysym as a raw keycode
+            enigo.try_key_click(Key::Raw(u16::from(keycode)))
+        })
+    }
+    /// Type `text`, entering its line breaks as described by `newline`.
+    ///
+    /// Use [ReturnKey](enum.NewlineMode.html#variant.ReturnKey) for
+    /// applications that ignore a literal newline character.
+    /// This is Linux-specific.
+    pub fn type_multiline(&mut self, text: &str, newline: NewlineMode) {
+        self.step(|enigo| {
+            if newline == NewlineMode::Literal {
+                enigo.key_sequence(text);
+                return;
+            }
+            for (i, line) in split_lines(text).into_iter().enumerate() {
+                if enigo.cancelled() {
+                    return;
+                }
+                if i > 0 {
+                    enigo.key_click(Key::Return);
+                }
+                if !line.is_empty() {
+                    enigo.key_sequence(line);
+                }
+            }
+        })
+    }
+    /// Compile and apply the keymap for a comma separated list of XKB layouts,
+    /// e.g. `"us"`, `"us,de"` or `"de(nodeadkeys)"`, to type against a known
+    /// layout regardless of the host's keyboard configuration.
+    ///
+    /// The keymap is loaded on the X server, so it applies to all clients and
+    /// stays until the keyboard configuration is changed again, e.g. by
+    /// `setxkbmap`. Returns an error if the server can't build it.
+    /// This is Linux-specific.
+    pub fn load_keymap(&mut self, keymap: &str) -> Result<(), EnigoError> {
+        self.handle_errors(|enigo| {
+            let error = || EnigoError::UnknownKeymap(keymap.to_string());
+            let symbols = keymap_symbols(keymap).ok_or_else(error)?;
+            let mut keycodes = CString::new("evdev+aliases(qwerty)").unwrap().into_bytes_with_nul();
+            let mut complete = CString::new("complete").unwrap().into_bytes_with_nul();
+            let mut compat = complete.clone();
+            let mut symbols = CString::new(symbols).map_err(|_| error())?.into_bytes_with_nul();
+            let mut names = xlib::XkbComponentNamesRec {
+                keymap: ptr::null_mut(),
+                keycodes: keycodes.as_mut_ptr() as *mut c_char,
+                types: complete.as_mut_ptr() as *mut c_char,
+                compat: compat.as_mut_ptr() as *mut c_char,
+                symbols: symbols.as_mut_ptr() as *mut c_char,
+                geometry: ptr::null_mut(),
+            };
+            unsafe {
+                let xkb = xlib::XkbGetKeyboardByName(
+                    enigo.display(),
+                    xlib::XKB_USE_CORE_KBD,
+                    &mut names,
+                    xlib::XKB_GBN_ALL_COMPONENTS_MASK,
+                    xlib::XKB_GBN_ALL_COMPONENTS_MASK & !xlib::XKB_GBN_GEOMETRY_MASK,
+                    1,
+                );
+                if xkb.is_null() {
+                    return Err(error());
+                }
+                xlib::XkbFreeKeyboard(xkb, xlib::XKB_ALL_COMPONENTS_MASK, 1);
+            }
+            Ok(())
+        })
+    }
+    /// Get the key repeat delay and the number of repeats per second the X
+    /// server is configured with, to simulate held keys like the user's
+    /// keyboard would.
+    ///
+    /// The XKB defaults of 660ms and 25 repeats per second are returned if
+    /// XKB can't report them.
+    /// This is Linux-specific.
+    pub fn auto_repeat_rate(&self) -> (Duration, u32) {
+        let (mut delay, mut interval) = (0, 0);
+        let ok = unsafe {
+            xlib::XkbGetAutoRepeatRate(self.display(), xlib::XKB_USE_CORE_KBD, &mut delay, &mut interval)
+        };
+        if ok == xlib::FALSE || interval == 0 {
+            return DEFAULT_AUTO_REPEAT;
+        }
+        (Duration::from_millis(u64::from(delay)), 1000 / interval)
+    }
+    /// Type `text` only if `expected` is the focused window, to never type
+    /// into the wrong window.
+    ///
+    /// Returns an error naming the focused window otherwise, or the error of
+    /// [try_key_sequence](#method.try_key_sequence) if the text couldn't be
+    /// typed.
+    ///
+    /// The check is best-effort: the focus can still move between it and
+    /// the typing, e.g. to a window popping up right then. A server grab
+    /// would close that gap, but freeze all other clients for as long as the
+    /// text takes to type.
+    /// This is Linux-specific.
+    pub fn key_sequence_if_focused(&mut self, expected: i32, text: &str) -> Result<(), EnigoError> {
+        self.step(|enigo| {
+            enigo.handle_errors(|enigo| {
+                let actual = enigo.focused_window();
+                if actual != Some(expected) {
+                    return Err(EnigoError::FocusMismatch { expected, actual });
+                }
+                Ok(())
+            })?;
+            enigo.try_key_sequence(text)
+        })
+    }
+    /// Type `text`, clicking Tab for `\t` and Return for `\n`, `\r` and
+    /// `\r\n` instead of entering them as characters, e.g. to fill a form
+    /// from a single template string.
+    /// This is Linux-specific.
+    pub fn type_interpreting_controls(&mut self, text: &str) {
+        self.step(|enigo| {
+            for (chunk, key) in split_controls(text) {
+                if enigo.cancelled() {
+                    return;
+                }
+                if !chunk.is_empty() {
+                    enigo.key_sequence(chunk);
+                }
+                if let Some(key) = key {
+                    enigo.key_click(key);
+                }
+            }
+        })
+    }
+    /// Block until `cond` holds, checking it every `poll`, e.g. to wait for
+    /// a window to show up or for the focus to change:
+    ///
+    /// ```no_run
+    /// # use enigo::Enigo;
+    /// # use std::time::Duration;
+    /// let mut enigo = Enigo::new();
+    /// let before = enigo.focused_window();
+    /// enigo.wait_until(Duration::from_secs(5), Duration::from_millis(50), |enigo| {
+    ///     enigo.focused_window() != before
+    /// });
+    /// ```
+    ///
+    /// Returns true as soon as the condition is met, or false once `timeout`
+    /// passed without it. The condition is checked at least once.
+    /// This is Linux-specific.
+    pub fn wait_until(
+        &mut self,
+        timeout: Duration,
+        poll: Duration,
+        cond: impl Fn(&Enigo) -> bool,
+    ) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if cond(self) {
+                return true;
+            }
+            let now = Instant::now();
+            if now >= deadline || self.cancelled() {
+                return false;
+            }
+            thread::sleep(poll.min(deadline - now));
+        }
+    }
+    /// Get the human readable name of the active keyboard layout, e.g.
+    /// `English (US)`, as reported by the XKB group names.
+    ///
+    /// Returns `None` if the active group has no name.
+    /// This is Linux-specific.
+    pub fn active_layout_name(&self) -> Option<String> {
+        let mut state = xlib::XkbStateRec::default();
+        let status = unsafe { xlib::XkbGetState(self.display(), xlib::XKB_USE_CORE_KBD, &mut state) };
+        if status != xlib::SUCCESS {
+            return None;
+        }
+        let atom = self.with_xkb_names(xlib::XKB_GROUP_NAMES_MASK, |_, names| {
+            names.groups[usize::from(state.group) % names.groups.len()]
+        })?;
+        if atom == 0 {
+            return None;
+        }
+        self.atom_name(atom)
+    }
+    /// Move the mouse through `points` as one continuous stroke taking
+    /// `total_duration`.
+    ///
+    /// Sparse points are joined by straight lines, so the pointer never jumps
+    /// more than a few pixels at a time, and the duration is spread evenly
+    /// over the whole path. The points are scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    /// This is Linux-specific.
+    pub fn mouse_stroke(&mut self, points: &[(i32, i32)], total_duration: Duration) {
+        self.step(|enigo| {
+            let points: Vec<_> = points.iter().map(|&(x, y)| enigo.scaled(x, y)).collect();
+            enigo.stroke_raw(&points, total_duration);
+        })
+    }
+    /// Move the mouse to the center of `window`.
+    ///
+    /// The window geometry is already in screen pixels, so no scale factor
+    /// is applied. Does nothing if the window geometry can't be read.
+    /// This is Linux-specific.
+    pub fn mouse_move_to_window_center(&mut self, window: i32) {
+        self.step(|enigo| {
+            if let (Some((x, y)), Some((width, height))) =
+                (enigo.window_location(window), enigo.window_size(window))
+            {
+                enigo.move_pointer(x + (width / 2) as i32, y + (height / 2) as i32);
+            }
+        })
+    }
+    /// Move the mouse in a straight line from where it is to `to` at a speed
+    /// of `px_per_sec` pixels per second.
+    ///
+    /// The target is scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to),
+    /// the speed is in screen pixels. A speed that isn't positive jumps
+    /// right to the target.
+    /// This is Linux-specific.
+    pub fn mouse_move_at_velocity(&mut self, to: (i32, i32), px_per_sec: f64) {
+        self.step(|enigo| {
+            let to = enigo.scaled(to.0, to.1);
+            let from = enigo.mouse_location();
+            let distance = f64::from(to.0 - from.0).hypot(f64::from(to.1 - from.1));
+            if px_per_sec > 0.0 && px_per_sec.is_finite() {
+                enigo.stroke_raw(&[from, to], Duration::from_secs_f64(distance / px_per_sec));
+            } else {
+                enigo.move_pointer(to.0, to.1);
+            }
+        })
+    }
+    /// Move the mouse to `x`, `y` relative to the top left corner of the
+    /// current window ID, so the target stays the same wherever the window
+    /// was moved. With the default window (0) this is the same as
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    ///
+    /// The offsets are scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    /// This is Linux-specific.
+    pub fn mouse_move_to_window(&mut self, x: i32, y: i32) {
+        self.step(|enigo| {
+            let window = enigo.window;
+            if window as Window == CURRENT_WINDOW {
+                enigo.mouse_move_to(x, y);
+                return;
+            }
+            let (x, y) = enigo.scaled(x, y);
+            if enigo.virtual_input.is_some() {
+                if let Some((window_x, window_y)) = enigo.window_location(window) {
+                    let _ = enigo.uinput_call(|device| device.move_to(window_x + x, window_y + y));
+                }
+                return;
+            }
+            let _ = enigo.xdo_call(|enigo| unsafe {
+                xdo_move_mouse_relative_to_window(enigo.xdo, window as Window, x, y)
+            });
+        })
+    }
+    /// Move the mouse in a straight line from where it is to `x`, `y` over
+    /// `duration_ms` milliseconds, for applications that only react to
+    /// motion along a path, like hover menus.
+    ///
+    /// The number of steps grows with the distance, so that the pointer
+    /// moves a few pixels at a time. The target is scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    /// This is Linux-specific.
+    pub fn mouse_move_smooth(&mut self, x: i32, y: i32, duration_ms: u64) {
+        self.step(|enigo| {
+            let to = enigo.scaled(x, y);
+            let from = enigo.mouse_location();
+            enigo.stroke_raw(&[from, to], Duration::from_millis(duration_ms));
+        })
+    }
+    /// The pointer position relative to the root window of its screen,
+    /// whatever window enigo is bound to. The position isn't scaled.
+    /// This is Linux-specific.
+    pub fn mouse_location(&self) -> (i32, i32) {
+        let (x, y, _) = self.mouse_location_full();
+        (x, y)
+    }
+    /// Like [mouse_location](#method.mouse_location), followed by the number
+    /// of the screen the pointer is on.
+    /// This is Linux-specific.
+    pub fn mouse_location_full(&self) -> (i32, i32, i32) {
+        let (mut x, mut y, mut screen) = (0, 0, 0);
+        unsafe {
+            xdo_get_mouse_location(self.xdo, &mut x, &mut y, &mut screen);
+        }
+        (x, y, screen)
+    }
+    /// Press `button` at the first point of `path`, move through the other
+    /// points waiting `step_delay` before each, and release it at the last
+    /// one, e.g. for a lasso or rubber-band selection.
+    ///
+    /// The points are scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    /// The button is released even when the
+    /// [cancel flag](#method.set_cancel_flag) stops the gesture early.
+    /// This is Linux-specific.
+    pub fn mouse_lasso(&mut self, path: &[(i32, i32)], button: MouseButton, step_delay: Duration) {
+        self.step(|enigo| {
+            let (&(x, y), rest) = match path.split_first() {
+                Some(split) => split,
+                None => return,
+            };
+            enigo.mouse_move_to(x, y);
+            enigo.mouse_down(button);
+            for &(x, y) in rest {
+                if enigo.cancelled() {
+                    break;
+                }
+                thread::sleep(step_delay);
+                enigo.mouse_move_to(x, y);
+            }
+            enigo.mouse_up(button);
+        })
+    }
+    /// Emit random mouse moves, clicks, scrolls and keystrokes for
+    /// `duration`, e.g. to monkey-test the input handling of an application.
+    ///
+    /// The pointer stays within `bounds`, given as x, y, width and height and
+    /// scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    /// The same `seed` produces the same sequence of actions. Key
+    /// combinations that leave the application under test, like
+    /// Ctrl+Alt+Backspace, Ctrl+Alt+F1 or Alt+F4, are never pressed, and
+    /// the Super key isn't used at all. The
+    /// [cancel flag](#method.set_cancel_flag) stops the burst early.
+    /// This is Linux-specific.
+    pub fn fuzz(&mut self, duration: Duration, seed: u64, bounds: (i32, i32, i32, i32)) {
+        let (left, top, width, height) = bounds;
+        let mut rng = FuzzRng::new(seed);
+        let end = Instant::now() + duration;
+        while Instant::now() < end && !self.cancelled() {
+            match rng.below(10) {
+                0..=3 => {
+                    let x = left + rng.below(width.max(1) as u64) as i32;
+                    let y = top + rng.below(height.max(1) as u64) as i32;
+                    self.mouse_move_to(x, y);
+                }
+                4 | 5 => {
+                    let button = [MouseButton::Left, MouseButton::Middle, MouseButton::Right];
+                    self.mouse_click(button[rng.below(3) as usize]);
+                }
+                6 => {
+                    let length = rng.below(7) as i32 - 3;
+                    self.mouse_scroll_y(length);
+                }
+                _ => {
+                    let key = FUZZ_KEYS[rng.below(FUZZ_KEYS.len() as u64) as usize];
+                    let modifiers: Vec<Key> = FUZZ_MODIFIERS
+                        .iter()
+                        .copied()
+                        .filter(|_| rng.below(4) == 0)
+                        .collect();
+                    if !fuzz_blocked(&modifiers, key) {
+                        self.key_combo(&modifiers, key);
+                    }
+                }
+            }
+            thread::sleep(Duration::from_millis(5 + rng.below(45)));
+        }
+    }
+    /// Drag with `button` from `from` to `to`: move to `from`, press the
+    /// button, move along a straight line to `to` and release it.
+    ///
+    /// The points are scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    /// The button is released even when the
+    /// [cancel flag](#method.set_cancel_flag) stops the move early.
+    /// This is Linux-specific.
+    pub fn mouse_drag(&mut self, button: MouseButton, from: (i32, i32), to: (i32, i32)) {
+        self.step(|enigo| {
+            enigo.mouse_move_to(from.0, from.1);
+            enigo.mouse_down(button);
+            let path = [enigo.scaled(from.0, from.1), enigo.scaled(to.0, to.1)];
+            enigo.stroke_raw(&path, DRAG_DURATION);
+            enigo.mouse_up(button);
+        })
+    }
+    /// Get the longest time between two clicks that still make a
+    /// double-click, from the `Net/DoubleClickTime` of the XSETTINGS
+    /// manager, e.g. gnome-settings-daemon or xfsettingsd.
+    ///
+    /// Without a manager the GTK default of 400ms is returned.
+    /// This is Linux-specific.
+    pub fn double_click_time(&self) -> Duration {
+        let display = self.display();
+        let name = format!("_XSETTINGS_S{}", unsafe { xlib::XDefaultScreen(display) });
+        let selection = match self.intern_atom(&name) {
+            Some(atom) => atom,
+            None => return DEFAULT_DOUBLE_CLICK_TIME,
+        };
+        let manager = unsafe { xlib::XGetSelectionOwner(display, selection) };
+        if manager == xlib::NONE {
+            return DEFAULT_DOUBLE_CLICK_TIME;
+        }
+        self.window_property(manager, "_XSETTINGS_SETTINGS", |format, data, nitems| {
+            if format != 8 {
+                return None;
+            }
+            let data = unsafe { std::slice::from_raw_parts(data, nitems) };
+            xsettings::integer(data, "Net/DoubleClickTime")
+        })
+        .flatten()
+        .filter(|&time| time > 0)
+        .map_or(DEFAULT_DOUBLE_CLICK_TIME, |time| Duration::from_millis(time as u64))
+    }
+    /// Click `button` `count` times in a row, fast enough for applications
+    /// to count them as one double, triple, quadruple, … click.
+    ///
+    /// The clicks are a quarter of the
+    /// [double_click_time](#method.double_click_time) apart, plus the
+    /// [mouse_delay](#method.mouse_delay) a button is held down.
+    /// This is Linux-specific.
+    pub fn mouse_multi_click(&mut self, button: MouseButton, count: u32) {
+        self.step(|enigo| {
+            let gap = enigo.double_click_time() / 4;
+            enigo.click_burst(button, count, gap);
+        })
+    }
+    /// Click `button` `count` times with the
+    /// [mouse_delay](#method.mouse_delay) both as the time a button is held
+    /// and as the pause between clicks, for a burst as tight as the
+    /// application accepts. See
+    /// [mouse_multi_click](#method.mouse_multi_click) for clicks spaced by
+    /// the desktop's double-click time.
+    /// This is Linux-specific.
+    pub fn mouse_click_n(&mut self, button: MouseButton, count: u32) {
+        self.step(|enigo| {
+            let gap = Duration::from_micros(enigo.mouse_delay);
+            enigo.click_burst(button, count, gap);
+        })
+    }
+    /// Double-click `button`, see [mouse_click_n](#method.mouse_click_n).
+    /// This is Linux-specific.
+    pub fn mouse_double_click(&mut self, button: MouseButton) {
+        self.mouse_click_n(button, 2);
+    }
+    /// Get the origin of
+    /// [click_relative_to_anchor](#method.click_relative_to_anchor).
+    /// Default value is (0, 0).
+    /// This is Linux-specific.
+    pub fn anchor(&self) -> (i32, i32) {
+        self.anchor
+    }
+    /// Set the origin of
+    /// [click_relative_to_anchor](#method.click_relative_to_anchor), e.g.
+    /// the top left corner of a grid of buttons located once.
+    /// This is Linux-specific.
+    pub fn set_anchor(&mut self, x: i32, y: i32) {
+        self.anchor = (x, y);
+    }
+    /// Click `button` at `dx`, `dy` from the [anchor](#method.set_anchor),
+    /// wherever the pointer went in between.
+    ///
+    /// The point is scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    /// This is Linux-specific.
+    pub fn click_relative_to_anchor(&mut self, dx: i32, dy: i32, button: MouseButton) {
+        self.step(|enigo| {
+            let (x, y) = enigo.anchor;
+            enigo.mouse_move_to(x + dx, y + dy);
+            enigo.mouse_click(button);
+        })
+    }
+    /// Pinch with two fingers on a horizontal line through `center`, moving
+    /// them from `from_spread` to `to_spread` pixels apart over `duration`.
+    /// Spreading them apart zooms in, bringing them together zooms out.
+    ///
+    /// X has no way for clients to fake touch input, so this creates a
+    /// virtual touch screen through uinput the first time, which needs write
+    /// access to `/dev/uinput`. The touch screen is expected to be mapped to
+    /// the whole X screen. The coordinates are scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    /// This is Linux-specific.
+    pub fn pinch(
+        &mut self,
+        center: (i32, i32),
+        from_spread: i32,
+        to_spread: i32,
+        duration: Duration,
+    ) -> io::Result<()> {
+        self.step(|enigo| {
+            let (x, y) = enigo.scaled(center.0, center.1);
+            let from = f64::from(from_spread) * enigo.scale_factor;
+            let to = f64::from(to_spread) * enigo.scale_factor;
+            let frames = ((duration.as_micros() / TOUCH_FRAME.as_micros()) as u32).max(1);
+            let fingers = |frame: u32| {
+                let spread = from + (to - from) * f64::from(frame) / f64::from(frames);
+                let half = (spread / 2.0).round() as i32;
+                [(x - half, y), (x + half, y)]
+            };
+
+            let cancel = enigo.cancel.clone();
+            let touch = enigo.touch_device()?;
+            touch.touch_down(&fingers(0))?;
+            for frame in 1..=frames {
+                if cancel.as_ref().is_some_and(|flag| flag.load(Ordering::SeqCst)) {
+                    break;
+                }
+                thread::sleep(TOUCH_FRAME);
+                touch.touch_move(&fingers(frame))?;
+            }
+            touch.touch_up(2)
+        })
+    }
+    /// Swipe with `fingers` fingers in `direction` over `distance` touchpad
+    /// units during `duration`, e.g. three fingers to the left for a
+    /// workspace switch. Two fingers scroll, three and more make the
+    /// swipe gesture of the desktop.
+    ///
+    /// X has no way for clients to fake gestures, so this creates a virtual
+    /// touchpad of 2000x2000 units through uinput the first time, which
+    /// needs write access to `/dev/uinput`. Swipes start in the middle of
+    /// the touchpad, so longer distances are cut at its edge.
+    /// This is Linux-specific.
+    pub fn swipe(
+        &mut self,
+        direction: Direction,
+        fingers: u8,
+        distance: i32,
+        duration: Duration,
+    ) -> io::Result<()> {
+        self.step(|enigo| {
+            let count = i32::from(fingers.clamp(1, 5));
+            let center = TOUCHPAD_SIZE / 2;
+            let distance = distance.clamp(0, center - 1);
+            let (dx, dy): (i32, i32) = match direction {
+                Direction::Up => (0, -1),
+                Direction::Down => (0, 1),
+                Direction::Left => (-1, 0),
+                Direction::Right => (1, 0),
+            };
+            let frames = ((duration.as_micros() / TOUCH_FRAME.as_micros()) as u32).max(1);
+            // the fingers sit side by side across the direction of the swipe
+            let positions = |frame: u32| -> Vec<(i32, i32)> {
+                let moved = (f64::from(distance) * f64::from(frame) / f64::from(frames)).round() as i32;
+                (0..count)
+                    .map(|finger| {
+                        let offset = (2 * finger - (count - 1)) * FINGER_SPACING / 2;
+                        let x = center + dx * moved + dy.abs() * offset;
+                        let y = center + dy * moved + dx.abs() * offset;
+                        (x, y)
+                    })
+                    .collect()
+            };
+
+            let cancel = enigo.cancel.clone();
+            let touchpad = enigo.touchpad_device()?;
+            touchpad.touch_down(&positions(0))?;
+            for frame in 1..=frames {
+                if cancel.as_ref().is_some_and(|flag| flag.load(Ordering::SeqCst)) {
+                    break;
+                }
+                thread::sleep(TOUCH_FRAME);
+                touchpad.touch_move(&positions(frame))?;
+            }
+            touchpad.touch_up(count as usize)
+        })
+    }
+    /// Click `key` while holding `modifiers`, e.g. Ctrl+C with
+    /// `key_combo(&[Key::Control], Key::Layout('c'))`. Any key can act as a
+    /// modifier, including [ModeSwitch](enum.Key.html#variant.ModeSwitch).
+    ///
+    /// The modifiers are pressed in order, then
+    /// [modifier_settle](#method.set_modifier_settle) passes before the key
+    /// is clicked, and the modifiers are released in reverse order.
+    /// This is Linux-specific.
+    pub fn key_combo(&mut self, modifiers: &[Key], key: Key) {
+        let _ = self.try_key_combo(modifiers, key);
+    }
+    /// Like [key_combo](#method.key_combo), but returns the first error of
+    /// pressing, clicking or releasing a key. All modifiers are released
+    /// even when a step fails, so none of them stays held.
+    /// This is Linux-specific.
+    pub fn try_key_combo(&mut self, modifiers: &[Key], key: Key) -> Result<(), EnigoError> {
+        self.step(|enigo| {
+            let mut result = Ok(());
+            for &modifier in modifiers {
+                result = result.and(enigo.key_event(modifier, true));
+            }
+            if !modifiers.is_empty() {
+                thread::sleep(enigo.modifier_settle);
+            }
+            if result.is_ok() {
+                result = enigo.try_key_click(key);
+            }
+            for &modifier in modifiers.iter().rev() {
+                let released = enigo.key_event(modifier, false);
+                result = result.and(released);
+            }
+            result
+        })
+    }
+    /// Click `key` and return the window that has the focus afterwards, e.g.
+    /// to check the tab order of a dialog.
+    ///
+    /// The key is waited for to reach the X server, then for up to 200ms
+    /// for the focus to leave the window focused before, see
+    /// [key_click_and_get_focus_within](#method.key_click_and_get_focus_within)
+    /// for another timeout. Widgets that aren't X windows of their own share
+    /// the focus of their top-level window, so moving between them doesn't
+    /// show up here.
+    /// This is Linux-specific.
+    pub fn key_click_and_get_focus(&mut self, key: Key) -> Option<i32> {
+        self.key_click_and_get_focus_within(key, FOCUS_CHANGE_TIMEOUT)
+    }
+    /// Like [key_click_and_get_focus](#method.key_click_and_get_focus), but
+    /// waits up to `timeout` for the focus to move. It returns as soon as
+    /// the focus moved, so the whole timeout only passes for keys that keep
+    /// the focus where it is, e.g. a zero timeout for keys known not to move
+    /// it.
+    /// This is Linux-specific.
+    pub fn key_click_and_get_focus_within(&mut self, key: Key, timeout: Duration) -> Option<i32> {
+        let before = self.focused_window();
+        self.key_click(key);
+        unsafe {
+            xlib::XSync(self.display(), xlib::FALSE);
+        }
+        self.wait_until(timeout, Duration::from_millis(10), |enigo| {
+            enigo.focused_window() != before
+        });
+        self.focused_window()
+    }
+    /// Like [key_sequence](trait.KeyboardControllable.html#tymethod.key_sequence),
+    /// but fails with `InvalidInput` if `sequence` contains a NUL byte and
+    /// with `XdoFailed` if libxdo couldn't type it.
+    ///
+    /// Long text is typed in pieces of 256 characters, with the
+    /// [delay](#method.delay) between them. On the uinput backend every line
+    /// break is typed as Return, and a character the keyboard layout can't
+    /// type fails with `InvalidInput` before any of the text is typed.
+    /// This is Linux-specific.
+    pub fn try_key_sequence(&mut self, sequence: &str) -> Result<(), EnigoError> {
+        self.step(|enigo| {
+            if enigo.virtual_input.is_some() {
+                let codes = text_keys(sequence, |key| enigo.evdev_key(key).ok())
+                    .map_err(|c| EnigoError::InvalidInput(c.to_string()))?;
+                for (code, shifted) in codes {
+                    if enigo.cancelled() {
+                        break;
+                    }
+                    enigo.uinput_code(code, shifted, true)?;
+                    enigo.uinput_code(code, shifted, false)?;
+                    thread::sleep(Duration::from_micros(enigo.text_delay()));
+                }
+                return Ok(());
+            }
+            if sequence.contains('\0') {
+                return Err(EnigoError::InvalidInput(sequence.to_string()));
+            }
+            for (i, chunk) in text_chunks(sequence, TEXT_CHUNK).into_iter().enumerate() {
+                if i > 0 {
+                    if enigo.cancelled() {
+                        break;
+                    }
+                    thread::sleep(Duration::from_micros(enigo.delay));
+                }
+                let string = CString::new(chunk).unwrap();
+                enigo.xdo_call(|enigo| unsafe {
+                    xdo_enter_text_window(
+                        enigo.xdo,
+                        enigo.window as Window,
+                        string.as_ptr(),
+                        enigo.text_delay() as useconds_t,
+                    )
+                })?;
+            }
+            Ok(())
+        })
+    }
+    /// Like [key_click](trait.KeyboardControllable.html#tymethod.key_click),
+    /// but fails with `XdoFailed` if libxdo couldn't send the key.
+    /// This is Linux-specific.
+    pub fn try_key_click(&mut self, key: Key) -> Result<(), EnigoError> {
+        self.step(|enigo| {
+            if enigo.virtual_input.is_some() {
+                enigo.uinput_key(key, true)?;
+                thread::sleep(Duration::from_micros(enigo.delay));
+                return enigo.uinput_key(key, false);
+            }
+            let sequence = keysequence(key);
+            enigo.xdo_call(|enigo| enigo.send_keysequence_delayed(&sequence, enigo.delay))
+        })
+    }
+    /// Like [key_down](trait.KeyboardControllable.html#tymethod.key_down),
+    /// but fails with `XdoFailed` if libxdo couldn't send the key, e.g. for
+    /// an invalid window or a keysym it can't map.
+    /// This is Linux-specific.
+    pub fn try_key_down(&mut self, key: Key) -> Result<(), EnigoError> {
+        self.step(|enigo| {
+            enigo.key_event(key, true)
+        })
+    }
+    /// Like [key_up](trait.KeyboardControllable.html#tymethod.key_up), but
+    /// fails with `XdoFailed` if libxdo couldn't send the key.
+    /// This is Linux-specific.
+    pub fn try_key_up(&mut self, key: Key) -> Result<(), EnigoError> {
+        self.step(|enigo| {
+            enigo.key_event(key, false)
+        })
+    }
+    /// Type `text` into the widget with the accessible role `role` (e.g.
+    /// `text` or `entry`) and the accessible name `name`, after giving it
+    /// the focus through AT-SPI. This reaches toolkit text fields that
+    /// aren't X windows of their own, so window searches can't find them.
+    ///
+    /// Returns `UnknownAccessible` if no such widget exists or it can't take
+    /// the focus. This needs the `with_atspi` feature and libatspi.
+    /// This is Linux-specific.
+    #[cfg(feature = "with_atspi")]
+    pub fn type_into_accessible(&mut self, role: &str, name: &str, text: &str) -> Result<(), EnigoError> {
+        if !atspi::focus(role, name) {
+            return Err(EnigoError::UnknownAccessible {
+                role: role.to_string(),
+                name: name.to_string(),
+            });
+        }
+        self.try_key_sequence(text)
+    }
+    /// Press `key`, hold it for exactly `hold` and release it again.
+    ///
+    /// Unlike [key_click](trait.KeyboardControllable.html#tymethod.key_click)
+    /// no `delay` is added, which lets you tune taps that have to look like a
+    /// real keypress, e.g. a Super tap opening the launcher.
+    /// This is Linux-specific.
+    pub fn tap_key(&mut self, key: Key, hold: Duration) {
+        let _ = self.try_tap_key(key, hold);
+    }
+    /// Like [tap_key](#method.tap_key), but returns the error of pressing
+    /// or releasing the key. The key is released even when pressing it
+    /// failed, so it doesn't stay held.
+    /// This is Linux-specific.
+    pub fn try_tap_key(&mut self, key: Key, hold: Duration) -> Result<(), EnigoError> {
+        self.step(|enigo| {
+            let pressed = enigo.key_event_delayed(key, true, 0);
+            if pressed.is_ok() {
+                thread::sleep(hold);
+            }
+            let released = enigo.key_event_delayed(key, false, 0);
+            pressed.and(released)
+        })
+    }
+    /// Press `key` and keep it down until the returned guard is dropped:
+    ///
+    /// ```no_run
+    /// # use enigo::{Enigo, Key, KeyboardControllable};
+    /// let mut enigo = Enigo::new();
+    /// {
+    ///     let mut shift = enigo.key_hold(Key::Shift);
+    ///     shift.key_click(Key::Layout('a'));
+    /// }
+    /// // Shift is up again here
+    /// ```
+    /// This is Linux-specific.
+    pub fn key_hold(&mut self, key: Key) -> KeyGuard<'_> {
+        self.key_down(key);
+        KeyGuard { enigo: self, key }
+    }
+    /// Type the code points of `grapheme` back-to-back through their Unicode
+    /// keysyms, so that e.g. a flag emoji or a letter followed by combining
+    /// marks arrives in order without a delay the application could use to
+    /// normalize the parts on their own.
+    /// This is Linux-specific.
+    pub fn type_grapheme(&mut self, grapheme: &str) {
+        self.step(|enigo| {
+            if enigo.virtual_input.is_some() {
+                for c in grapheme.chars() {
+                    let _ = enigo.uinput_key(Key::Layout(c), true);
+                    let _ = enigo.uinput_key(Key::Layout(c), false);
+                }
+            } else if !grapheme.is_empty() {
+                let sequence = unicode_keysequence(grapheme);
+                let _ = enigo.xdo_call(|enigo| enigo.send_keysequence_delayed(&sequence, 0));
+            }
+        })
+    }
+    /// Type `text` one key click per character through its Unicode keysym,
+    /// like [key_click](trait.KeyboardControllable.html#tymethod.key_click)
+    /// with `Key::Layout`. This is slower than
+    /// [key_sequence](trait.KeyboardControllable.html#tymethod.key_sequence),
+    /// but gets accented characters, emoji and other scripts right with
+    /// layouts on which libxdo's text typing mangles them.
+    ///
+    /// Tabs and line breaks are typed as Tab and Return.
+    /// This is Linux-specific.
+    pub fn key_sequence_unicode(&mut self, text: &str) {
+        self.step(|enigo| {
+            for c in text.chars() {
+                if enigo.cancelled() {
+                    break;
+                }
+                let key = match c {
+                    '\t' => Key::Tab,
+                    '\n' => Key::Return,
+                    c => Key::Layout(c),
+                };
+                let _ = enigo.try_key_click(key);
+            }
+        })
+    }
+    /// Delete `count` characters before the caret by clicking Backspace
+    /// `count` times, `delay` apart.
+    /// This is Linux-specific.
+    pub fn backspace(&mut self, count: u32) {
+        self.step(|enigo| {
+            let _ = enigo.repeat_key(Key::Backspace, count);
+        })
+    }
+    /// Delete `count` characters after the caret by clicking Delete `count`
+    /// times, `delay` apart.
+    /// This is Linux-specific.
+    pub fn delete_forward(&mut self, count: u32) {
+        self.step(|enigo| {
+            let _ = enigo.repeat_key(Key::Delete, count);
+        })
+    }
+    /// Parse and run a script like
+    /// `"move 100,200; click left; type hello; key Return; sleep 500"`.
+    ///
+    /// See [script::parse](script/fn.parse.html) for the grammar. Nothing is
+    /// run if the script doesn't parse.
+    /// This is Linux-specific.
+    pub fn run_script(&mut self, script: &str) -> Result<(), ScriptError> {
+        let actions = script::parse(script)?;
+        self.run(&actions);
+        Ok(())
+    }
+    /// Run the actions one after another.
+    /// This is Linux-specific.
+    pub fn run(&mut self, actions: &[Action]) {
+        for action in actions {
+            if self.cancelled() {
+                return;
+            }
+            script::execute(self, action);
+        }
+    }
+    /// Run the actions one after another, waiting the given time before
+    /// each, e.g. to replay a macro recorded with its pauses.
+    /// This is Linux-specific.
+    pub fn run_timed(&mut self, actions: &[(Duration, Action)]) {
+        self.run_timed_scaled(actions, 1.0);
+    }
+    /// Like [run_timed](#method.run_timed), but with the waits divided by
+    /// `speed`, so that 0.5 replays in slow motion and 2.0 twice as fast.
+    /// A speed that isn't positive runs the actions without waiting.
+    /// This is Linux-specific.
+    pub fn run_timed_scaled(&mut self, actions: &[(Duration, Action)], speed: f64) {
+        for (delay, action) in actions {
+            if self.cancelled() {
+                return;
+            }
+            thread::sleep(scaled_delay(*delay, speed));
+            if self.cancelled() {
+                return;
+            }
+            script::execute(self, action);
+        }
+    }
+    /// Execute `action` and measure the time until the X server reports the
+    /// resulting input event, e.g. for a latency benchmark of the injection
+    /// path.
+    ///
+    /// The event is watched for as an XInput2 raw event on a separate
+    /// connection, so any key, button or motion event counts, including
+    /// ones from real devices.
+    ///
+    /// Returns `None` if the server doesn't support XInput 2.0 or no event
+    /// shows up within a second, e.g. for a `Sleep` action. There is no
+    /// latency to measure in these cases, and a zero or timeout `Duration`
+    /// would pass as a real measurement in a benchmark.
+    /// This is Linux-specific.
+    pub fn measure_input_latency(&mut self, action: Action) -> Option<Duration> {
+        let name = CString::new(XExtension::XInput2.name()).unwrap();
+        unsafe {
+            let display_name = self.display_name().map_or(ptr::null(), CStr::as_ptr);
+            let display = xlib::XOpenDisplay(display_name);
+            if display.is_null() {
+                return None;
+            }
+            let (mut opcode, mut first_event, mut first_error) = (0, 0, 0);
+            if xlib::XQueryExtension(display, name.as_ptr(), &mut opcode, &mut first_event, &mut first_error)
+                == xlib::FALSE
+            {
+                xlib::XCloseDisplay(display);
+                return None;
+            }
+            // the server has to be told that the client speaks XI2 before
+            // it accepts XI2 event masks
+            let (mut major, mut minor) = (2, 0);
+            if xlib::XIQueryVersion(display, &mut major, &mut minor) != xlib::SUCCESS {
+                xlib::XCloseDisplay(display);
+                return None;
+            }
+            let raw_events = [
+                xlib::XI_RAW_KEY_PRESS,
+                xlib::XI_RAW_KEY_RELEASE,
+                xlib::XI_RAW_BUTTON_PRESS,
+                xlib::XI_RAW_BUTTON_RELEASE,
+                xlib::XI_RAW_MOTION,
+            ];
+            let mut bits = [0u8; 3];
+            for &raw in &raw_events {
+                bits[raw as usize / 8] |= 1 << (raw % 8);
+            }
+            let mut mask = xlib::XIEventMask {
+                deviceid: xlib::XI_ALL_MASTER_DEVICES,
+                mask_len: bits.len() as c_int,
+                mask: bits.as_mut_ptr(),
+            };
+            let (_, error) = xerror::trap(display, || {
+                xlib::XISelectEvents(display, xlib::XDefaultRootWindow(display), &mut mask, 1)
+            });
+            if error.is_some() {
+                xlib::XCloseDisplay(display);
+                return None;
+            }
 
-        if length < 0 {
-            length = -length;
+            let start = Instant::now();
+            script::execute(self, &action);
+            xlib::XFlush(self.display());
+            let mut latency = None;
+            let mut event: xlib::XEvent = std::mem::zeroed();
+            while latency.is_none() && start.elapsed() < LATENCY_TIMEOUT {
+                if xlib::XPending(display) == 0 {
+                    thread::sleep(Duration::from_micros(100));
+                    continue;
+                }
+                xlib::XNextEvent(display, &mut event);
+                if event.type_ == xlib::GENERIC_EVENT
+                    && event.generic.extension == opcode
+                    && raw_events.contains(&event.generic.evtype)
+                {
+                    latency = Some(start.elapsed());
+                }
+            }
+            xlib::XCloseDisplay(display);
+            latency
+        }
+    }
+    /// Execute `action` at the instant `at` from a timer thread, e.g. to line
+    /// up a click with an external event.
+    ///
+    /// The action is sent through its own connection to the X server with
+    /// the same settings, backend and error handler as this instance. Use
+    /// the returned handle to cancel it before it is due.
+    ///
+    /// Fails with `NoDisplay` if that connection can't be opened and with
+    /// `BackendFailed` if the backend can't be set up for it.
+    /// This is Linux-specific.
+    pub fn schedule(&mut self, at: Instant, action: Action) -> Result<ScheduledAction, EnigoError> {
+        let mut enigo = self.new_connection()?;
+        let (cancel, cancelled) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            let wait = at.saturating_duration_since(Instant::now());
+            match cancelled.recv_timeout(wait) {
+                Ok(()) => return,
+                Err(RecvTimeoutError::Timeout) => {}
+                // the handle was dropped, so nobody can cancel anymore
+                Err(RecvTimeoutError::Disconnected) => {
+                    thread::sleep(at.saturating_duration_since(Instant::now()))
+                }
+            }
+            script::execute(&mut enigo, &action);
+        });
+        Ok(ScheduledAction { cancel, thread })
+    }
+    /// Run the actions one after another, calling `check` after each of
+    /// them, e.g. to make sure the expected window still has the focus.
+    ///
+    /// Stops at the first action whose check fails and returns its index.
+    /// Like [run](#method.run), it also stops when the
+    /// [cancel flag](#method.set_cancel_flag) is set, but returns `Ok` then.
+    /// This is Linux-specific.
+    pub fn run_checked(
+        &mut self,
+        actions: &[Action],
+        check: impl Fn(&Enigo, &Action) -> bool,
+    ) -> Result<(), usize> {
+        for (index, action) in actions.iter().enumerate() {
+            if self.cancelled() {
+                break;
+            }
+            script::execute(self, action);
+            if !check(self, action) {
+                return Err(index);
+            }
+        }
+        Ok(())
+    }
+    /// Save the actions to a macro file, see
+    /// [script::save_macro](script/fn.save_macro.html).
+    /// This is Linux-specific.
+    #[cfg(feature = "with_serde")]
+    pub fn save_macro(&self, actions: &[Action], path: &Path) -> io::Result<()> {
+        script::save_macro(actions, path)
+    }
+    /// Load the actions from a macro file, see
+    /// [script::load_macro](script/fn.load_macro.html).
+    /// This is Linux-specific.
+    #[cfg(feature = "with_serde")]
+    pub fn load_macro(path: &Path) -> io::Result<Vec<Action>> {
+        script::load_macro(path)
+    }
+    /// Move the mouse to `x` and `y` relative to the top-left corner of the
+    /// monitor with the RandR name `name` (e.g. `HDMI-1` or `DP-2`).
+    ///
+    /// The local coordinates are scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    /// Returns an error if no monitor has that name.
+    /// This is Linux-specific.
+    pub fn mouse_move_to_monitor(&mut self, name: &str, x: i32, y: i32) -> Result<(), EnigoError> {
+        self.step(|enigo| {
+            enigo.handle_errors(|enigo| {
+                let monitor = enigo
+                    .monitors()
+                    .into_iter()
+                    .find(|monitor| monitor.name == name)
+                    .ok_or_else(|| EnigoError::UnknownMonitor(name.to_string()))?;
+                let (x, y) = enigo.scaled(x, y);
+                enigo.move_pointer(monitor.x + x, monitor.y + y);
+                Ok(())
+            })
+        })
+    }
+    /// Check whether the X server supports `ext`, to fail fast on minimal
+    /// servers instead of calling methods that need it.
+    /// This is Linux-specific.
+    pub fn has_extension(&self, ext: XExtension) -> bool {
+        let name = CString::new(ext.name()).unwrap();
+        let (mut opcode, mut event, mut error) = (0, 0, 0);
+        unsafe {
+            xlib::XQueryExtension(self.display(), name.as_ptr(), &mut opcode, &mut event, &mut error)
+                != xlib::FALSE
+        }
+    }
+    /// Check whether the X server takes injected input, e.g. to tell the
+    /// user at startup that a hardened server drops it.
+    ///
+    /// This nudges the pointer by one pixel with a relative XTest motion,
+    /// reads the position back and moves the pointer back where it was. A
+    /// pointer that can't move, e.g. while another client grabs it, also
+    /// counts as blocked.
+    /// This is Linux-specific.
+    pub fn can_inject(&self) -> bool {
+        if !self.has_extension(XExtension::XTest) {
+            return false;
         }
+        let (x, y) = self.mouse_location();
+        let step = if x > 0 { -1 } else { 1 };
+        unsafe {
+            // unlike xdo_move_mouse, which warps the pointer, this goes
+            // through XTestFakeRelativeMotionEvent
+            if xdo_move_mouse_relative(self.xdo, step, 0) != 0 {
+                return false;
+            }
+            xlib::XSync(self.display(), xlib::FALSE);
+        }
+        let moved = self.mouse_location() == (x + step, y);
+        if moved {
+            unsafe {
+                xdo_move_mouse_relative(self.xdo, -step, 0);
+                xlib::XFlush(self.display());
+            }
+        }
+        moved
+    }
+    /// Get whether this instance keeps the screen saver from blanking the
+    /// screen, see [inhibit_screensaver](#method.inhibit_screensaver).
+    /// This is Linux-specific.
+    pub fn screensaver_inhibited(&self) -> bool {
+        self.screensaver_inhibited
+    }
+    /// Keep the screen saver and DPMS from blanking the screen while `inhibit`
+    /// is true, e.g. during a long automation run.
+    ///
+    /// The prior state comes back with `inhibit_screensaver(false)` or when
+    /// the Enigo instance is dropped.
+    /// This is Linux-specific.
+    pub fn inhibit_screensaver(&mut self, inhibit: bool) {
+        if inhibit == self.screensaver_inhibited || !self.has_extension(XExtension::ScreenSaver) {
+            return;
+        }
+        let display = self.display();
+        unsafe {
+            xlib::XScreenSaverSuspend(display, inhibit as xlib::Bool);
+            xlib::XFlush(display);
+        }
+        self.screensaver_inhibited = inhibit;
+    }
+    /// Check whether a compositing manager runs on the default screen, i.e.
+    /// whether someone owns the `_NET_WM_CM_Sn` selection.
+    /// This is Linux-specific.
+    pub fn is_compositing(&self) -> bool {
+        let display = self.display();
+        unsafe {
+            let screen = xlib::XDefaultScreen(display);
+            match self.intern_atom(&format!("_NET_WM_CM_S{}", screen)) {
+                Some(selection) => xlib::XGetSelectionOwner(display, selection) != 0,
+                None => false,
+            }
+        }
+    }
+    /// Try to take the input lock shared by all enigo instances on the
+    /// display, so that two automation processes don't type into each
+    /// other's keystrokes. Returns false if someone else holds it.
+    ///
+    /// The lock is the `ENIGO_INPUT_LOCK` selection, held by a hidden window
+    /// until [release_input_lock](#method.release_input_lock) or until the
+    /// connection closes, so a crashed process doesn't keep it.
+    /// This is Linux-specific.
+    pub fn try_acquire_input_lock(&mut self) -> bool {
+        if self.input_lock.is_some() {
+            return true;
+        }
+        let display = self.display();
+        let selection = match self.intern_atom(INPUT_LOCK_SELECTION) {
+            Some(atom) => atom,
+            None => return false,
+        };
+        unsafe {
+            // no one may take the selection between looking and taking it
+            xlib::XGrabServer(display);
+            let mut window = None;
+            if xlib::XGetSelectionOwner(display, selection) == xlib::NONE {
+                let root = xlib::XDefaultRootWindow(display);
+                let owner = xlib::XCreateSimpleWindow(display, root, 0, 0, 1, 1, 0, 0, 0);
+                xlib::XSetSelectionOwner(display, selection, owner, xlib::CURRENT_TIME);
+                if xlib::XGetSelectionOwner(display, selection) == owner {
+                    window = Some(owner);
+                } else {
+                    xlib::XDestroyWindow(display, owner);
+                }
+            }
+            xlib::XUngrabServer(display);
+            xlib::XFlush(display);
+            self.input_lock = window;
+        }
+        self.input_lock.is_some()
+    }
+    /// Give up the input lock taken by
+    /// [try_acquire_input_lock](#method.try_acquire_input_lock).
+    /// This is Linux-specific.
+    pub fn release_input_lock(&mut self) {
+        if let Some(window) = self.input_lock.take() {
+            let display = self.display();
+            unsafe {
+                // destroying the owner releases the selection
+                xlib::XDestroyWindow(display, window);
+                xlib::XFlush(display);
+            }
+        }
+    }
+    /// List the pointer barriers created by
+    /// [create_pointer_barrier](#method.create_pointer_barrier).
+    ///
+    /// X doesn't let clients see the barriers of other clients, e.g. ones
+    /// a desktop puts between monitors. When a move to another monitor
+    /// stops short, comparing the target with
+    /// [mouse_location](#method.mouse_location) is the only hint there is.
+    /// This is Linux-specific.
+    pub fn pointer_barriers(&self) -> Vec<Barrier> {
+        self.barriers.clone()
+    }
+    /// Create an XFixes pointer barrier along the horizontal or vertical
+    /// line from `from` to `to` that keeps every pointer from crossing it,
+    /// e.g. to hold the cursor on one monitor. The coordinates are relative
+    /// to the root window and aren't scaled.
+    ///
+    /// The barrier lasts until it's destroyed or this Enigo is dropped.
+    /// Returns an error if the X server refuses it, e.g. for a diagonal
+    /// line or without XFixes 5.
+    /// This is Linux-specific.
+    pub fn create_pointer_barrier(
+        &mut self,
+        from: (i32, i32),
+        to: (i32, i32),
+    ) -> Result<Barrier, EnigoError> {
+        let display = self.display();
+        let (id, error) = xerror::trap(display, || unsafe {
+            let root = xlib::XDefaultRootWindow(display);
+            let (x1, y1, x2, y2) = (from.0, from.1, to.0, to.1);
+            // no directions and no devices mean blocking all pointers both ways
+            xlib::XFixesCreatePointerBarrier(display, root, x1, y1, x2, y2, 0, 0, ptr::null_mut())
+        });
+        if let Some((code, resource)) = error {
+            return Err(EnigoError::XRequestFailed { code, resource });
+        }
+        let barrier = Barrier {
+            id: id as u32,
+            from,
+            to,
+        };
+        self.barriers.push(barrier);
+        Ok(barrier)
+    }
+    /// Remove a barrier created by
+    /// [create_pointer_barrier](#method.create_pointer_barrier).
+    /// This is Linux-specific.
+    pub fn destroy_pointer_barrier(&mut self, barrier: Barrier) {
+        if let Some(index) = self.barriers.iter().position(|&known| known == barrier) {
+            self.barriers.remove(index);
+            unsafe {
+                xlib::XFixesDestroyPointerBarrier(self.display(), c_ulong::from(barrier.id));
+                xlib::XFlush(self.display());
+            }
+        }
+    }
+    /// List the master and slave input devices known to XInput2.
+    ///
+    /// Returns an empty list if XInput2 isn't available.
+    /// This is Linux-specific.
+    pub fn list_input_devices(&self) -> Vec<InputDevice> {
+        if !self.has_extension(XExtension::XInput2) {
+            return Vec::new();
+        }
+        let mut count = 0;
+        unsafe {
+            let infos = xlib::XIQueryDevice(self.display(), xlib::XI_ALL_DEVICES, &mut count);
+            if infos.is_null() {
+                return Vec::new();
+            }
+            let devices = std::slice::from_raw_parts(infos, count as usize)
+                .iter()
+                .map(|info| InputDevice {
+                    id: info.deviceid,
+                    name: if info.name.is_null() {
+                        String::new()
+                    } else {
+                        CStr::from_ptr(info.name).to_string_lossy().into_owned()
+                    },
+                    device_type: match info.use_ {
+                        xlib::XI_MASTER_POINTER => DeviceType::MasterPointer,
+                        xlib::XI_MASTER_KEYBOARD => DeviceType::MasterKeyboard,
+                        xlib::XI_SLAVE_POINTER => DeviceType::SlavePointer,
+                        xlib::XI_SLAVE_KEYBOARD => DeviceType::SlaveKeyboard,
+                        _ => DeviceType::FloatingSlave,
+                    },
+                })
+                .collect();
+            xlib::XIFreeDeviceInfo(infos);
+            devices
+        }
+    }
+    /// Get the name of the current cursor image in the cursor theme, e.g.
+    /// `xterm` for a text beam or `left_ptr` for the default arrow.
+    ///
+    /// Returns `None` if the cursor has no name, e.g. because the application
+    /// drew its own.
+    /// This is Linux-specific.
+    pub fn cursor_name(&self) -> Option<String> {
+        unsafe {
+            let image = xlib::XFixesGetCursorImage(self.display());
+            if image.is_null() {
+                return None;
+            }
+            let name = if (*image).name.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr((*image).name).to_string_lossy().into_owned())
+            };
+            xlib::XFree(image as *mut c_void);
+            name.filter(|name| !name.is_empty())
+        }
+    }
+    /// Click `button` at `x` and `y` relative to the top-left corner of the
+    /// monitor at index `monitor`, in the order RandR lists the monitors.
+    ///
+    /// The local coordinates are scaled like in
+    /// [mouse_move_to](trait.MouseControllable.html#tymethod.mouse_move_to).
+    /// Returns an error if there is no monitor at that index.
+    /// This is Linux-specific.
+    pub fn click_on_monitor(
+        &mut self,
+        monitor: usize,
+        x: i32,
+        y: i32,
+        button: MouseButton,
+    ) -> Result<(), EnigoError> {
+        self.step(|enigo| {
+            enigo.handle_errors(|enigo| {
+                let target = enigo
+                    .monitors()
+                    .into_iter()
+                    .nth(monitor)
+                    .ok_or_else(|| EnigoError::UnknownMonitor(monitor.to_string()))?;
+                let (x, y) = enigo.scaled(x, y);
+                enigo.move_pointer(target.x + x, target.y + y);
+                enigo.mouse_click(button);
+                Ok(())
+            })
+        })
+    }
+    /// Get the x, y, width and height of the RandR monitor containing the
+    /// point `x`, `y` in root window coordinates.
+    ///
+    /// Returns `None` if the point is off all monitors.
+    /// This is Linux-specific.
+    pub fn monitor_at(&self, x: i32, y: i32) -> Option<(i32, i32, u32, u32)> {
+        self.monitors()
+            .into_iter()
+            .find(|monitor| monitor.contains(x, y))
+            .map(|monitor| (monitor.x, monitor.y, monitor.width, monitor.height))
+    }
+
+    /// Get the left, top, right and bottom edge of the rectangle spanning all
+    /// RandR monitors, gaps between them included. The right and bottom
+    /// edges are exclusive, like `x + width`.
+    ///
+    /// Falls back to the size of the root window without RandR monitors.
+    /// This is Linux-specific.
+    pub fn virtual_screen_bounds(&self) -> (i32, i32, i32, i32) {
+        bounding_box(&self.monitors()).unwrap_or_else(|| {
+            let (width, height) = self.root_size();
+            (0, 0, width, height)
+        })
+    }
+    /// Get the width and height in pixels of the X screen the pointer is on.
+    ///
+    /// An X screen usually spans all monitors, see
+    /// [monitor_at](#method.monitor_at) for the size of a single one.
+    /// This is Linux-specific.
+    pub fn main_display_size(&self) -> (i32, i32) {
+        let (_, _, screen) = self.mouse_location_full();
+        let (mut width, mut height) = (0, 0);
+        unsafe {
+            xdo_get_viewport_dimensions(self.xdo, &mut width, &mut height, screen);
+        }
+        (width as i32, height as i32)
+    }
+    /// Get the number of X screens of the display. This is 1 on almost all
+    /// setups, which put every monitor on one screen.
+    /// This is Linux-specific.
+    pub fn screen_count(&self) -> i32 {
+        unsafe { xlib::XScreenCount(self.display()) }
+    }
+
+    // `xdo_t` starts with the `Display *` it was opened with
+    fn display(&self) -> *mut xlib::Display {
+        unsafe { *(self.xdo as *const *mut xlib::Display) }
+    }
+
+    // The display given to with_display, if any
+    fn display_name(&self) -> Option<&CStr> {
+        self.display_name.as_deref()
+    }
+
+    fn monitors(&self) -> Vec<Monitor> {
+        let display = self.display();
+        let mut count = 0;
+        unsafe {
+            let root = xlib::XDefaultRootWindow(display);
+            let infos = xlib::XRRGetMonitors(display, root, 1, &mut count);
+            if infos.is_null() {
+                return Vec::new();
+            }
+            let monitors = std::slice::from_raw_parts(infos, count as usize)
+                .iter()
+                .map(|info| Monitor {
+                    name: self.atom_name(info.name).unwrap_or_default(),
+                    x: info.x,
+                    y: info.y,
+                    width: info.width as u32,
+                    height: info.height as u32,
+                })
+                .collect();
+            xlib::XRRFreeMonitors(infos);
+            monitors
+        }
+    }
+
+    fn with_xkb_names<T>(
+        &self,
+        which: c_uint,
+        f: impl FnOnce(&xlib::XkbDescRec, &xlib::XkbNamesRec) -> T,
+    ) -> Option<T> {
+        let display = self.display();
+        unsafe {
+            let xkb = xlib::XkbGetMap(display, 0, xlib::XKB_USE_CORE_KBD);
+            if xkb.is_null() {
+                return None;
+            }
+            let result = if xlib::XkbGetNames(display, which, xkb) == xlib::SUCCESS
+                && !(*xkb).names.is_null()
+            {
+                Some(f(&*xkb, &*(*xkb).names))
+            } else {
+                None
+            };
+            xlib::XkbFreeKeyboard(xkb, xlib::XKB_ALL_COMPONENTS_MASK, 1);
+            result
+        }
+    }
+
+    // Find the keycode of a physical key by its XKB name
+    fn keycode_of(&self, key: VirtualKey) -> Option<u8> {
+        let mut wanted = [0; 4];
+        for (byte, name) in wanted.iter_mut().zip(key.xkb_name().bytes()) {
+            *byte = name as c_char;
+        }
+        self.with_xkb_names(xlib::XKB_KEY_NAMES_MASK, |xkb, names| {
+            if names.keys.is_null() {
+                return None;
+            }
+            (xkb.min_key_code..=xkb.max_key_code)
+                .find(|&keycode| unsafe { *names.keys.add(usize::from(keycode)) } == wanted)
+        })
+        .and_then(|keycode| keycode)
+    }
+
+    fn atom_name(&self, atom: xlib::Atom) -> Option<String> {
+        unsafe { take_x_string(xlib::XGetAtomName(self.display(), atom)) }
+    }
+
+    fn stroke_raw(&mut self, points: &[(i32, i32)], duration: Duration) {
+        let path = interpolate(points, STROKE_STEP);
+        let start = Instant::now();
+        for (step, &(x, y)) in path.iter().enumerate() {
+            if self.cancelled() {
+                return;
+            }
+            self.move_pointer(x, y);
+            let due = start + duration * (step as u32 + 1) / path.len() as u32;
+            let now = Instant::now();
+            if due > now {
+                thread::sleep(due - now);
+            }
+        }
+    }
+
+    // Another instance with the same settings and backend on its own
+    // connection to the display this one is on
+    fn new_connection(&self) -> Result<Enigo, EnigoError> {
+        let name = unsafe { CStr::from_ptr(xlib::XDisplayString(self.display())) };
+        let mut enigo = Enigo::with_display(&name.to_string_lossy())?;
+        if self.virtual_input.is_some() {
+            enigo.open_virtual_input()?;
+        }
+        enigo.delay = self.delay;
+        enigo.mouse_delay = self.mouse_delay;
+        enigo.window = self.window;
+        enigo.scale_factor = self.scale_factor;
+        enigo.invert_scroll_x = self.invert_scroll_x;
+        enigo.invert_scroll_y = self.invert_scroll_y;
+        enigo.click_strategy = self.click_strategy;
+        enigo.cancel = self.cancel.clone();
+        enigo.adaptive_typing = self.adaptive_typing;
+        enigo.typing_delay = self.typing_delay;
+        enigo.typing_schedule = self.typing_schedule;
+        enigo.clean_typings = self.clean_typings;
+        enigo.modifier_settle = self.modifier_settle;
+        enigo.error_handler = self.error_handler.clone();
+        enigo.step_delay = self.step_delay;
+        enigo.scroll_step = self.scroll_step;
+        enigo.anchor = self.anchor;
+        Ok(enigo)
+    }
+
+    fn touch_device(&mut self) -> io::Result<&mut uinput::TouchDevice> {
+        if self.touch.is_none() {
+            let (width, height) = self.root_size();
+            self.touch = Some(uinput::TouchDevice::new(width, height)?);
+        }
+        Ok(self.touch.as_mut().unwrap())
+    }
+
+    fn touchpad_device(&mut self) -> io::Result<&mut uinput::TouchDevice> {
+        if self.touchpad.is_none() {
+            self.touchpad = Some(uinput::TouchDevice::touchpad(TOUCHPAD_SIZE, TOUCHPAD_SIZE)?);
+        }
+        Ok(self.touchpad.as_mut().unwrap())
+    }
 
-        for _ in 0..length {
+    fn root_size(&self) -> (i32, i32) {
+        let display = self.display();
+        unsafe {
+            let mut attributes: xlib::XWindowAttributes = std::mem::zeroed();
+            xlib::XGetWindowAttributes(display, xlib::XDefaultRootWindow(display), &mut attributes);
+            (attributes.width, attributes.height)
+        }
+    }
+
+    // Click `count` times with `gap` between the clicks
+    fn click_burst(&mut self, button: MouseButton, count: u32, gap: Duration) {
+        for click in 0..count {
+            if click > 0 {
+                if self.cancelled() {
+                    break;
+                }
+                thread::sleep(gap);
+            }
             self.mouse_click(button);
         }
     }
-    fn mouse_scroll_y(&mut self, length: i32) {
-        let button;
-        let mut length = length;
 
-        if length < 0 {
-            button = MouseButton::ScrollUp;
+    // Count a text typed without drops, going back to the start of the
+    // typing schedule after enough of them
+    fn typed_without_drops(&mut self) {
+        let reset_after = self.typing_schedule.reset_after;
+        if !self.adaptive_typing || reset_after == 0 {
+            return;
+        }
+        self.clean_typings += 1;
+        if self.clean_typings >= reset_after {
+            self.typing_delay = self.typing_schedule.start;
+            self.clean_typings = 0;
+        }
+    }
+
+    fn text_delay(&self) -> u64 {
+        if self.adaptive_typing {
+            self.typing_delay
         } else {
-            button = MouseButton::ScrollDown;
+            self.delay
         }
+    }
 
-        if length < 0 {
-            length = -length;
+    fn cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::SeqCst))
+    }
+
+    // Move the pointer to `x`, `y` on the root window through the backend,
+    // without scaling
+    fn move_pointer(&mut self, x: i32, y: i32) {
+        if self.virtual_input.is_some() {
+            let _ = self.uinput_call(|device| device.move_to(x, y));
+            return;
         }
+        let _ = self.xdo_call(|enigo| unsafe { xdo_move_mouse(enigo.xdo, x as c_int, y as c_int, 0) });
+    }
 
-        for _ in 0..length {
-            self.mouse_click(button);
+    fn click_raw(&mut self, button: MouseButton) -> c_int {
+        let window = match self.click_strategy {
+            // xdo_click_window always waits DEFAULT_DELAY between press and release
+            ClickStrategy::WindowTargeted if self.mouse_delay == DEFAULT_DELAY => unsafe {
+                return xdo_click_window(self.xdo, self.window as Window, mousebutton(button));
+            },
+            ClickStrategy::WindowTargeted => self.window as Window,
+            ClickStrategy::PointerPress => CURRENT_WINDOW,
+        };
+        let down = unsafe { xdo_mouse_down(self.xdo, window, mousebutton(button)) };
+        thread::sleep(Duration::from_micros(self.mouse_delay));
+        let up = unsafe { xdo_mouse_up(self.xdo, window, mousebutton(button)) };
+        if down != 0 {
+            down
+        } else {
+            up
+        }
+    }
+
+    // Scroll `length` units with `negative` or `positive` depending on the
+    // sign, each unit being scroll_step button clicks without a pause and
+    // the units mouse_delay apart
+    fn scroll_raw(&mut self, length: i32, negative: MouseButton, positive: MouseButton) {
+        let pause = Duration::from_micros(self.mouse_delay);
+        if self.virtual_input.is_some() {
+            // the wheel turns up and right for positive values
+            let step = self.scroll_step.min(i32::MAX as u32) as i32 * length.signum();
+            let (vertical, horizontal) = if positive == MouseButton::ScrollDown {
+                (-step, 0)
+            } else {
+                (0, step)
+            };
+            for unit in 0..length.unsigned_abs() {
+                if self.cancelled() {
+                    break;
+                }
+                if unit > 0 {
+                    thread::sleep(pause);
+                }
+                let _ = self.uinput_call(|device| device.scroll(vertical, horizontal));
+            }
+            return;
+        }
+        let button = mousebutton(if length < 0 { negative } else { positive });
+        let step = self.scroll_step;
+        let notches = length.unsigned_abs().saturating_mul(step);
+        let window = match self.click_strategy {
+            ClickStrategy::WindowTargeted => self.window as Window,
+            ClickStrategy::PointerPress => CURRENT_WINDOW,
+        };
+        // a retry after a failure carries on with the remaining notches
+        let mut done = 0;
+        let _ = self.xdo_call(|enigo| {
+            while done < notches {
+                if enigo.cancelled() {
+                    break;
+                }
+                let code = unsafe {
+                    match xdo_mouse_down(enigo.xdo, window, button) {
+                        0 => xdo_mouse_up(enigo.xdo, window, button),
+                        code => code,
+                    }
+                };
+                if code != 0 {
+                    return code;
+                }
+                done += 1;
+                if done % step == 0 && done < notches {
+                    thread::sleep(pause);
+                }
+            }
+            0
+        });
+    }
+
+    // Press or release `key`, keeping track of the held keys
+    fn key_event(&mut self, key: Key, down: bool) -> Result<(), EnigoError> {
+        self.key_event_delayed(key, down, self.delay)
+    }
+
+    fn key_event_delayed(&mut self, key: Key, down: bool, delay: u64) -> Result<(), EnigoError> {
+        let result = if self.virtual_input.is_some() {
+            self.uinput_key(key, down)
+        } else {
+            let string = CString::new(&*keysequence(key)).unwrap();
+            self.xdo_call(|enigo| unsafe {
+                let send = if down {
+                    xdo_send_keysequence_window_down
+                } else {
+                    xdo_send_keysequence_window_up
+                };
+                send(enigo.xdo, enigo.window as Window, string.as_ptr(), delay as useconds_t)
+            })
+        };
+        if !down {
+            self.held.retain(|&held| held != key);
+        } else if result.is_ok() && !self.held.contains(&key) {
+            self.held.push(key);
+        }
+        result
+    }
+
+    // Press or release `key` on the uinput device, with shift around keys
+    // on the shift level
+    fn uinput_key(&mut self, key: Key, down: bool) -> Result<(), EnigoError> {
+        let (code, shifted) = self.evdev_key(key)?;
+        self.uinput_code(code, shifted, down)
+    }
+
+    // Press or release the evdev key `code`, with the left shift key around
+    // it if `shifted`
+    fn uinput_code(&mut self, code: u16, shifted: bool, down: bool) -> Result<(), EnigoError> {
+        self.uinput_call(|device| {
+            if shifted && down {
+                device.key(uinput::KEY_LEFTSHIFT, true)?;
+            }
+            device.key(code, down)?;
+            if shifted && !down {
+                device.key(uinput::KEY_LEFTSHIFT, false)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn uinput_button(&mut self, button: MouseButton, down: bool) -> Result<(), EnigoError> {
+        let index = match button {
+            MouseButton::Left => 0,
+            MouseButton::Right => 1,
+            MouseButton::Middle => 2,
+            MouseButton::Back => 3,
+            MouseButton::Forward => 4,
+            // a wheel notch is over with the press
+            _ if !down => return Ok(()),
+            MouseButton::ScrollUp => return self.uinput_call(|device| device.scroll(1, 0)),
+            MouseButton::ScrollDown => return self.uinput_call(|device| device.scroll(-1, 0)),
+            MouseButton::ScrollLeft => return self.uinput_call(|device| device.scroll(0, -1)),
+            MouseButton::ScrollRight => return self.uinput_call(|device| device.scroll(0, 1)),
+        };
+        self.uinput_call(|device| device.button(index, down))
+    }
+
+    // The evdev code of `key` in the current X keymap and whether it sits on
+    // the shift level
+    fn evdev_key(&self, key: Key) -> Result<(u16, bool), EnigoError> {
+        let unknown = || EnigoError::InvalidInput(format!("{:?}", key));
+        let keysym = match key {
+            // X keycodes are evdev codes shifted by 8
+            Key::Raw(keycode) => {
+                return keycode.checked_sub(8).map(|code| (code, false)).ok_or_else(unknown)
+            }
+            Key::Layout(c) => char_keysym(c),
+            _ => {
+                let sequence = keysequence(key);
+                // libxdo resolves these to the left modifier itself
+                let name = match &*sequence {
+                    "Alt" | "Option" => "Alt_L",
+                    "Control" => "Control_L",
+                    "Shift" => "Shift_L",
+                    "Super" => "Super_L",
+                    name => name,
+                };
+                let name = CString::new(name).unwrap();
+                unsafe { xlib::XStringToKeysym(name.as_ptr()) }
+            }
+        };
+        let display = self.display();
+        let keycode = unsafe { xlib::XKeysymToKeycode(display, keysym) };
+        if keysym == 0 || keycode < 8 {
+            return Err(unknown());
+        }
+        let shifted = unsafe { xlib::XkbKeycodeToKeysym(display, keycode, 0, 0) } != keysym;
+        Ok((u16::from(keycode) - 8, shifted))
+    }
+
+    // Run `op` on the uinput device through the error handler
+    fn uinput_call(
+        &mut self,
+        mut op: impl FnMut(&mut uinput::VirtualInput) -> io::Result<()>,
+    ) -> Result<(), EnigoError> {
+        self.handle_errors(|enigo| match enigo.virtual_input.as_mut() {
+            Some(device) => op(device).map_err(|error| EnigoError::BackendFailed(error.to_string())),
+            None => Err(EnigoError::BackendFailed("no uinput device".to_string())),
+        })
+    }
+
+    // Run an xdo call, returning non-zero on failure, through the error handler
+    fn xdo_call(&mut self, mut call: impl FnMut(&mut Enigo) -> c_int) -> Result<(), EnigoError> {
+        self.handle_errors(|enigo| {
+            let display = enigo.display();
+            match xerror::trap(display, || call(enigo)) {
+                (_, Some((code, resource))) => Err(EnigoError::XRequestFailed { code, resource }),
+                (0, None) => Ok(()),
+                (code, None) => Err(EnigoError::XdoFailed(code)),
+            }
+        })
+    }
+
+    // Run the public operation `op` and pause for the global step delay
+    // once it is over; operations it calls on the way don't pause
+    fn step<T>(&mut self, op: impl FnOnce(&mut Enigo) -> T) -> T {
+        self.step_depth += 1;
+        let result = op(self);
+        self.step_depth -= 1;
+        if self.step_depth == 0 {
+            thread::sleep(self.step_delay);
+        }
+        result
+    }
+
+    // Run `op` and ask the error handler what to do when it fails
+    fn handle_errors(
+        &mut self,
+        mut op: impl FnMut(&mut Enigo) -> Result<(), EnigoError>,
+    ) -> Result<(), EnigoError> {
+        let mut retries = 0;
+        loop {
+            let error = match op(self) {
+                Ok(()) => return Ok(()),
+                Err(error) => error,
+            };
+            let action = match self.error_handler {
+                Some(ref handler) => (handler.lock().unwrap_or_else(PoisonError::into_inner))(&error),
+                None => return Err(error),
+            };
+            match action {
+                ErrorAction::Retry if retries < ERROR_RETRIES => retries += 1,
+                ErrorAction::Ignore => return Ok(()),
+                _ => return Err(error),
+            }
+        }
+    }
+
+    // One key sequence lets xdo space the clicks by `delay`
+    fn repeat_key(&mut self, key: Key, count: u32) -> Result<(), EnigoError> {
+        if self.virtual_input.is_some() {
+            for click in 0..count {
+                if click > 0 {
+                    thread::sleep(Duration::from_micros(self.delay));
+                }
+                self.try_key_click(key)?;
+            }
+            return Ok(());
+        }
+        if count == 0 {
+            return Ok(());
+        }
+        let sequence = vec![keysequence(key); count as usize].join(" ");
+        self.xdo_call(|enigo| enigo.send_keysequence_delayed(&sequence, enigo.delay))
+    }
+
+    fn send_keysequence_delayed(&mut self, sequence: &str, delay: u64) -> c_int {
+        let string = CString::new(sequence).unwrap();
+        unsafe {
+            xdo_send_keysequence_window(
+                self.xdo,
+                self.window as Window,
+                string.as_ptr(),
+                delay as useconds_t,
+            )
+        }
+    }
+
+    fn scaled(&self, x: i32, y: i32) -> (i32, i32) {
+        (
+            (f64::from(x) * self.scale_factor).round() as i32,
+            (f64::from(y) * self.scale_factor).round() as i32,
+        )
+    }
+}
+impl Drop for Enigo {
+    fn drop(&mut self) {
+        self.inhibit_screensaver(false);
+        self.release_input_lock();
+        unsafe {
+            xdo_free(self.xdo);
         }
     }
 }
+impl MouseControllable for Enigo {
+    fn mouse_move_to(&mut self, x: i32, y: i32) {
+        self.step(|enigo| {
+            let (x, y) = enigo.scaled(x, y);
+            enigo.move_pointer(x, y);
+        })
+    }
+    fn mouse_move_relative(&mut self, x: i32, y: i32) {
+        self.step(|enigo| {
+            if enigo.virtual_input.is_some() {
+                let (from_x, from_y) = enigo.mouse_location();
+                let _ = enigo.uinput_call(|device| device.move_to(from_x + x, from_y + y));
+                return;
+            }
+            let _ = enigo.xdo_call(|enigo| unsafe {
+                xdo_move_mouse_relative(enigo.xdo, x as c_int, y as c_int)
+            });
+        })
+    }
+    fn mouse_down(&mut self, button: MouseButton) {
+        self.step(|enigo| {
+            if enigo.virtual_input.is_some() {
+                let _ = enigo.uinput_button(button, true);
+                return;
+            }
+            let _ = enigo.xdo_call(|enigo| unsafe {
+                xdo_mouse_down(enigo.xdo, enigo.window as Window, mousebutton(button))
+            });
+        })
+    }
+    fn mouse_up(&mut self, button: MouseButton) {
+        self.step(|enigo| {
+            if enigo.virtual_input.is_some() {
+                let _ = enigo.uinput_button(button, false);
+                return;
+            }
+            let _ = enigo.xdo_call(|enigo| unsafe {
+                xdo_mouse_up(enigo.xdo, enigo.window as Window, mousebutton(button))
+            });
+        })
+    }
+    fn mouse_click(&mut self, button: MouseButton) {
+        self.step(|enigo| {
+            if enigo.virtual_input.is_some() {
+                let _ = enigo.uinput_button(button, true);
+                thread::sleep(Duration::from_micros(enigo.mouse_delay));
+                let _ = enigo.uinput_button(button, false);
+                return;
+            }
+            let _ = enigo.xdo_call(|enigo| enigo.click_raw(button));
+        })
+    }
+    fn mouse_scroll_x(&mut self, length: i32) {
+        self.step(|enigo| {
+            let length = if enigo.invert_scroll_x { -length } else { length };
+            enigo.scroll_raw(length, MouseButton::ScrollLeft, MouseButton::ScrollRight);
+        })
+    }
+    fn mouse_scroll_y(&mut self, length: i32) {
+        self.step(|enigo| {
+            let length = if enigo.invert_scroll_y { -length } else { length };
+            enigo.scroll_raw(length, MouseButton::ScrollUp, MouseButton::ScrollDown);
+        })
+    }
+}
 fn keysequence<'a>(key: Key) -> Cow<'a, str> {
     if let Key::Layout(c) = key {
         return Cow::Owned(format!("U{:X}", c as u32));
@@ -270,65 +4306,276 @@ fn keysequence<'a>(key: Key) -> Cow<'a, str> {
         Key::F8 => "F8",
         Key::F9 => "F9",
         Key::Home => "Home",
+        Key::Insert => "Insert",
         Key::Layout(_) => unreachable!(),
         Key::LeftArrow => "Left",
+        Key::MediaNext => "XF86AudioNext",
+        Key::MediaPlayPause => "XF86AudioPlay",
+        Key::MediaPrev => "XF86AudioPrev",
+        Key::MediaStop => "XF86AudioStop",
+        Key::Menu => "Menu",
+        Key::ModeSwitch => "Mode_switch",
+        Key::NumLock => "Num_Lock",
+        Key::Numpad0 => "KP_0",
+        Key::Numpad1 => "KP_1",
+        Key::Numpad2 => "KP_2",
+        Key::Numpad3 => "KP_3",
+        Key::Numpad4 => "KP_4",
+        Key::Numpad5 => "KP_5",
+        Key::Numpad6 => "KP_6",
+        Key::Numpad7 => "KP_7",
+        Key::Numpad8 => "KP_8",
+        Key::Numpad9 => "KP_9",
+        Key::NumpadAdd => "KP_Add",
+        Key::NumpadDecimal => "KP_Decimal",
+        Key::NumpadDivide => "KP_Divide",
+        Key::NumpadEnter => "KP_Enter",
+        Key::NumpadMultiply => "KP_Multiply",
+        Key::NumpadSubtract => "KP_Subtract",
         Key::Option => "Option",
         Key::PageDown => "Page_Down",
         Key::PageUp => "Page_Up",
+        Key::Pause => "Pause",
+        Key::PrintScreen => "Print",
         Key::Raw(_) => unreachable!(),
         Key::Return => "Return",
         Key::RightArrow => "Right",
+        Key::ScrollLock => "Scroll_Lock",
         Key::Shift => "Shift",
         Key::Space => "space",
         Key::Tab => "Tab",
         Key::UpArrow => "Up",
+        Key::VolumeDown => "XF86AudioLowerVolume",
+        Key::VolumeMute => "XF86AudioMute",
+        Key::VolumeUp => "XF86AudioRaiseVolume",
 
         Key::Command | Key::Super | Key::Windows | Key::Meta => "Super",
     })
 }
 impl KeyboardControllable for Enigo {
     fn key_sequence(&mut self, sequence: &str) {
-        let string = CString::new(sequence).unwrap();
-        unsafe {
-            xdo_enter_text_window(
-                self.xdo,
-                self.window,
-                string.as_ptr(),
-                self.delay as useconds_t,
-            );
-        }
+        self.step(|enigo| {
+            let _ = enigo.try_key_sequence(sequence);
+        })
     }
     fn key_down(&mut self, key: Key) {
-        let string = CString::new(&*keysequence(key)).unwrap();
-        unsafe {
-            xdo_send_keysequence_window_down(
-                self.xdo,
-                self.window,
-                string.as_ptr(),
-                self.delay as useconds_t,
-            );
-        }
+        self.step(|enigo| {
+            let _ = enigo.key_event(key, true);
+        })
     }
     fn key_up(&mut self, key: Key) {
-        let string = CString::new(&*keysequence(key)).unwrap();
-        unsafe {
-            xdo_send_keysequence_window_up(
-                self.xdo,
-                self.window,
-                string.as_ptr(),
-                self.delay as useconds_t,
-            );
-        }
+        self.step(|enigo| {
+            let _ = enigo.key_event(key, false);
+        })
     }
     fn key_click(&mut self, key: Key) {
-        let string = CString::new(&*keysequence(key)).unwrap();
-        unsafe {
-            xdo_send_keysequence_window(
-                self.xdo,
-                self.window,
-                string.as_ptr(),
-                self.delay as useconds_t,
-            );
+        self.step(|enigo| {
+            let _ = enigo.try_key_click(key);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_text_at_chars() {
+        assert_eq!(text_chunks("", 2), Vec::<&str>::new());
+        assert_eq!(text_chunks("abc", 4), vec!["abc"]);
+        assert_eq!(text_chunks("abcde", 2), vec!["ab", "cd", "e"]);
+        assert_eq!(text_chunks("äöüß", 3), vec!["äöü", "ß"]);
+    }
+    #[test]
+    fn keysyms_of_chars() {
+        assert_eq!(char_keysym('a'), 0x61);
+        assert_eq!(char_keysym('\u{e9}'), 0xe9);
+        assert_eq!(char_keysym('\u{20ac}'), 0x0100_20ac);
+    }
+    #[test]
+    fn interpolate_path() {
+        assert_eq!(interpolate(&[], 4.0), vec![]);
+        assert_eq!(interpolate(&[(3, 3)], 4.0), vec![(3, 3)]);
+        assert_eq!(
+            interpolate(&[(0, 0), (10, 0), (10, 2)], 4.0),
+            vec![(0, 0), (3, 0), (7, 0), (10, 0), (10, 2)]
+        );
+    }
+    #[test]
+    fn escape_regex_metacharacters() {
+        assert_eq!(escape_regex("plain title"), "plain title");
+        assert_eq!(
+            escape_regex("main.rs (~/src) [+] - Vim"),
+            "main\\.rs \\(~/src\\) \\[\\+\\] - Vim"
+        );
+        assert_eq!(escape_regex("a|b^c$"), "a\\|b\\^c\\$");
+    }
+    #[test]
+    fn monitor_contains_point() {
+        let monitor = Monitor {
+            name: "HDMI-1".into(),
+            x: 1920,
+            y: 0,
+            width: 1280,
+            height: 1024,
+        };
+        assert!(monitor.contains(1920, 0));
+        assert!(monitor.contains(3199, 1023));
+        assert!(!monitor.contains(3200, 500));
+        assert!(!monitor.contains(1919, 500));
+        assert!(!monitor.contains(2000, -1));
+    }
+    #[test]
+    fn unicode_keysym_names() {
+        assert_eq!(unicode_keysequence("e\u{301}"), "U0065 U0301");
+        assert_eq!(unicode_keysequence("\u{1F1FA}\u{1F1F8}"), "U1F1FA U1F1F8");
+    }
+    #[test]
+    fn monitors_bounding_box() {
+        let monitor = |x, y, width, height| Monitor {
+            name: String::new(),
+            x,
+            y,
+            width,
+            height,
+        };
+        assert_eq!(bounding_box(&[]), None);
+        assert_eq!(
+            bounding_box(&[monitor(0, 200, 1920, 1080), monitor(1920, 0, 1280, 1024)]),
+            Some((0, 0, 3200, 1280))
+        );
+        assert_eq!(
+            bounding_box(&[monitor(-1280, 0, 1280, 1024), monitor(0, 0, 1920, 1080)]),
+            Some((-1280, 0, 1920, 1080))
+        );
+    }
+    #[test]
+    fn keymap_symbols_from_layouts() {
+        assert_eq!(keymap_symbols("us").as_deref(), Some("pc+us+inet(evdev)"));
+        assert_eq!(
+            keymap_symbols("us, de(nodeadkeys)").as_deref(),
+            Some("pc+us+de(nodeadkeys):2+inet(evdev)")
+        );
+        assert_eq!(keymap_symbols(""), None);
+        assert_eq!(keymap_symbols("us,,de"), None);
+        assert_eq!(keymap_symbols("us+de"), None);
+    }
+    #[test]
+    fn split_at_line_breaks() {
+        assert_eq!(split_lines(""), vec![""]);
+        assert_eq!(split_lines("one\ntwo"), vec!["one", "two"]);
+        assert_eq!(split_lines("one\r\ntwo\rthree\n"), vec!["one", "two", "three", ""]);
+        assert_eq!(split_lines("\r\r\n"), vec!["", "", ""]);
+    }
+    #[test]
+    fn text_keys_stop_at_unmapped_char() {
+        let lookup = |key| match key {
+            Key::Layout('\u{e9}') => None,
+            key => Some(key),
+        };
+        assert_eq!(
+            text_keys("a\tb\r\nc\r", lookup),
+            Ok(vec![
+                Key::Layout('a'),
+                Key::Tab,
+                Key::Layout('b'),
+                Key::Return,
+                Key::Layout('c'),
+                Key::Return,
+            ])
+        );
+        assert_eq!(text_keys("caf\u{e9}!", lookup), Err('\u{e9}'));
+    }
+    #[test]
+    fn split_at_controls() {
+        assert_eq!(split_controls(""), vec![]);
+        assert_eq!(split_controls("plain"), vec![("plain", None)]);
+        assert_eq!(
+            split_controls("name\tmail\r\nbye\n"),
+            vec![
+                ("name", Some(Key::Tab)),
+                ("mail", Some(Key::Return)),
+                ("bye", Some(Key::Return)),
+            ]
+        );
+        assert_eq!(
+            split_controls("\r\r\na"),
+            vec![("", Some(Key::Return)), ("", Some(Key::Return)), ("a", None)]
+        );
+    }
+    #[test]
+    fn window_actions_from_atom_names() {
+        assert_eq!(
+            WindowAction::from_atom_name("_NET_WM_ACTION_MAXIMIZE_VERT"),
+            Some(WindowAction::MaximizeVertically)
+        );
+        assert_eq!(
+            WindowAction::from_atom_name("_NET_WM_ACTION_CLOSE"),
+            Some(WindowAction::Close)
+        );
+        assert_eq!(WindowAction::from_atom_name("_KDE_NET_WM_ACTION_X"), None);
+    }
+    #[test]
+    fn fuzz_rng_replays_from_seed() {
+        let mut first = FuzzRng::new(42);
+        let mut second = FuzzRng::new(42);
+        let sequence: Vec<u64> = (0..8).map(|_| first.below(100)).collect();
+        assert_eq!(sequence, (0..8).map(|_| second.below(100)).collect::<Vec<_>>());
+        assert!(sequence.iter().all(|&n| n < 100));
+        assert_ne!(FuzzRng::new(0).next(), 0);
+    }
+    #[test]
+    fn fuzz_blocks_dangerous_combos() {
+        assert!(fuzz_blocked(&[Key::Control, Key::Alt], Key::Backspace));
+        assert!(fuzz_blocked(&[Key::Alt, Key::Shift, Key::Control], Key::F2));
+        assert!(fuzz_blocked(&[Key::Alt], Key::F4));
+        assert!(!fuzz_blocked(&[Key::Control], Key::Backspace));
+        assert!(!fuzz_blocked(&[], Key::F4));
+        assert!(!fuzz_blocked(&[Key::Control, Key::Shift], Key::Layout('z')));
+    }
+    #[test]
+    fn typing_schedule_slows_down_to_ceiling() {
+        let schedule = TypingSchedule::default();
+        assert_eq!(schedule.slowed(1000), 2000);
+        assert_eq!(schedule.slowed(80_000), 100_000);
+        assert_eq!(schedule.slowed(100_000), 100_000);
+        let schedule = TypingSchedule { factor: 3, ..schedule };
+        assert_eq!(schedule.slowed(0), 3);
+        assert_eq!(schedule.slowed(u64::MAX), 100_000);
+    }
+
+    #[test]
+    fn delays_scaled_by_speed() {
+        let delay = Duration::from_millis(300);
+        assert_eq!(scaled_delay(delay, 1.0), delay);
+        assert_eq!(scaled_delay(delay, 0.5), Duration::from_millis(600));
+        assert_eq!(scaled_delay(delay, 2.0), Duration::from_millis(150));
+        assert_eq!(scaled_delay(delay, 0.0), Duration::from_secs(0));
+        assert_eq!(scaled_delay(delay, f64::INFINITY), Duration::from_secs(0));
+        assert_eq!(scaled_delay(delay, f64::NAN), Duration::from_secs(0));
+    }
+    #[test]
+    fn helpers_survive_destroyed_window() {
+        // needs an X server and is skipped without one
+        if std::env::var_os("DISPLAY").is_none() {
+            return;
         }
+        let enigo = Enigo::try_new().unwrap();
+        let display = enigo.display();
+        let window = unsafe {
+            let root = xlib::XDefaultRootWindow(display);
+            let window = xlib::XCreateSimpleWindow(display, root, 0, 0, 10, 10, 0, 0, 0);
+            xlib::XDestroyWindow(display, window);
+            xlib::XSync(display, xlib::FALSE);
+            window as i32
+        };
+        assert_eq!(enigo.window_geometry(window), (0, 0, 0, 0));
+        assert!(!enigo.window_is_viewable(window));
+        assert!(!enigo.window_accepts_input(window));
+        assert!(!enigo.send_click_to_window(window, 1, 1, MouseButton::Left));
+        assert!(enigo.window_properties(window).is_empty());
+        assert!(enigo.window_allowed_actions(window).is_empty());
+        let info = enigo.window_info(window);
+        assert_eq!((info.name, info.class, info.pid), (None, None, 0));
     }
 }
@@ -0,0 +1,119 @@
+//! Finding and focusing widgets through the AT-SPI accessibility bus, which
+//! reaches text fields that aren't X windows of their own.
+
+use libc::{c_char, c_int, c_void};
+use std::{ffi::CStr, ptr};
+
+// how deep into the accessible tree of an application the search goes
+const MAX_DEPTH: u32 = 64;
+
+enum AtspiAccessible {}
+enum AtspiComponent {}
+
+#[link(name = "atspi")]
+extern "C" {
+    fn atspi_init() -> c_int;
+    fn atspi_get_desktop(index: c_int) -> *mut AtspiAccessible;
+    fn atspi_accessible_get_child_count(
+        accessible: *mut AtspiAccessible,
+        error: *mut *mut c_void,
+    ) -> c_int;
+    fn atspi_accessible_get_child_at_index(
+        accessible: *mut AtspiAccessible,
+        index: c_int,
+        error: *mut *mut c_void,
+    ) -> *mut AtspiAccessible;
+    fn atspi_accessible_get_name(
+        accessible: *mut AtspiAccessible,
+        error: *mut *mut c_void,
+    ) -> *mut c_char;
+    fn atspi_accessible_get_role_name(
+        accessible: *mut AtspiAccessible,
+        error: *mut *mut c_void,
+    ) -> *mut c_char;
+    fn atspi_accessible_get_component_iface(
+        accessible: *mut AtspiAccessible,
+    ) -> *mut AtspiComponent;
+    fn atspi_component_grab_focus(component: *mut AtspiComponent, error: *mut *mut c_void) -> c_int;
+}
+
+#[link(name = "gobject-2.0")]
+extern "C" {
+    fn g_object_unref(object: *mut c_void);
+}
+
+#[link(name = "glib-2.0")]
+extern "C" {
+    fn g_free(data: *mut c_void);
+}
+
+/// Give the keyboard focus to the first widget with the accessible role
+/// `role` (e.g. `text` or `entry`) and the accessible name `name`, searching
+/// all applications on the desktop. Returns false if no such widget exists
+/// or it can't take the focus.
+pub fn focus(role: &str, name: &str) -> bool {
+    unsafe {
+        // 1 means that AT-SPI was initialized before
+        if atspi_init() > 1 {
+            return false;
+        }
+        let desktop = atspi_get_desktop(0);
+        if desktop.is_null() {
+            return false;
+        }
+        let found = find(desktop, role, name, 0);
+        g_object_unref(desktop as *mut c_void);
+        let accessible = match found {
+            Some(accessible) => accessible,
+            None => return false,
+        };
+        let component = atspi_accessible_get_component_iface(accessible);
+        let focused = !component.is_null() && atspi_component_grab_focus(component, ptr::null_mut()) != 0;
+        if !component.is_null() {
+            g_object_unref(component as *mut c_void);
+        }
+        g_object_unref(accessible as *mut c_void);
+        focused
+    }
+}
+
+// Depth-first search below `parent`, returning a reference the caller has to
+// release
+unsafe fn find(
+    parent: *mut AtspiAccessible,
+    role: &str,
+    name: &str,
+    depth: u32,
+) -> Option<*mut AtspiAccessible> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    for index in 0..atspi_accessible_get_child_count(parent, ptr::null_mut()) {
+        let child = atspi_accessible_get_child_at_index(parent, index, ptr::null_mut());
+        if child.is_null() {
+            continue;
+        }
+        let matches = take_string(atspi_accessible_get_role_name(child, ptr::null_mut())).as_deref()
+            == Some(role)
+            && take_string(atspi_accessible_get_name(child, ptr::null_mut())).as_deref() == Some(name);
+        if matches {
+            return Some(child);
+        }
+        let found = find(child, role, name, depth + 1);
+        g_object_unref(child as *mut c_void);
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+// Copy a string handed out by AT-SPI and free it
+unsafe fn take_string(string: *mut c_char) -> Option<String> {
+    if string.is_null() {
+        return None;
+    }
+    let copy = CStr::from_ptr(string).to_string_lossy().into_owned();
+    g_free(string as *mut c_void);
+    Some(copy)
+}
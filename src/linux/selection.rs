@@ -0,0 +1,166 @@
+//! Owning an X selection, so that other clients can paste or drop data put
+//! there by enigo.
+
+use super::xlib;
+use libc::{c_int, c_uchar};
+use std::{
+    ffi::{CStr, CString},
+    mem, ptr,
+    sync::mpsc,
+    thread,
+};
+
+const TEXT_TARGETS: &[&str] = &["UTF8_STRING", "STRING", "TEXT"];
+
+/// Take ownership of `selection` (e.g. `PRIMARY` or `CLIPBOARD`) and hand out
+/// `text` to every client asking for it, until another client takes the
+/// selection over. Returns false if the selection couldn't be acquired.
+///
+/// The selection is served from a background thread on its own connection to
+/// `display_name` (or `$DISPLAY`), so it stays available while the caller
+/// keeps using libxdo.
+pub fn serve(display_name: Option<&CStr>, selection: &str, text: String) -> bool {
+    let display_name = display_name.map(CStr::to_owned);
+    let selection = selection.to_string();
+    let (acquired, receiver) = mpsc::channel();
+    thread::spawn(move || unsafe {
+        let name = display_name.as_deref().map_or(ptr::null(), CStr::as_ptr);
+        let display = xlib::XOpenDisplay(name);
+        if display.is_null() {
+            let _ = acquired.send(false);
+            return;
+        }
+        match Owner::new(display, &selection, TEXT_TARGETS, text.into_bytes()) {
+            Some(owner) => {
+                let _ = acquired.send(true);
+                let mut event: xlib::XEvent = mem::zeroed();
+                loop {
+                    xlib::XNextEvent(display, &mut event);
+                    match event.type_ {
+                        xlib::SELECTION_CLEAR => break,
+                        xlib::SELECTION_REQUEST => owner.answer(&event.selection_request),
+                        _ => {}
+                    }
+                }
+            }
+            None => {
+                let _ = acquired.send(false);
+            }
+        }
+        xlib::XCloseDisplay(display);
+    });
+    receiver.recv().unwrap_or(false)
+}
+
+pub unsafe fn intern(display: *mut xlib::Display, name: &str) -> xlib::Atom {
+    let name = CString::new(name).unwrap();
+    xlib::XInternAtom(display, name.as_ptr(), xlib::FALSE)
+}
+
+/// A hidden window owning a selection. The caller has to feed it the
+/// `SelectionRequest` events of its display.
+///
+/// Data so large that it'd need the INCR protocol isn't supported.
+pub struct Owner {
+    display: *mut xlib::Display,
+    /// the hidden window
+    pub window: xlib::Window,
+    targets_atom: xlib::Atom,
+    targets: Vec<xlib::Atom>,
+    data: Vec<u8>,
+}
+
+impl Owner {
+    /// Take `selection` and offer `data` as each of `targets`, e.g.
+    /// `UTF8_STRING` or `text/uri-list`. Returns `None` if the selection
+    /// couldn't be acquired.
+    pub unsafe fn new(
+        display: *mut xlib::Display,
+        selection: &str,
+        targets: &[&str],
+        data: Vec<u8>,
+    ) -> Option<Owner> {
+        let selection = intern(display, selection);
+        let root = xlib::XDefaultRootWindow(display);
+        let owner = Owner {
+            display,
+            window: xlib::XCreateSimpleWindow(display, root, 0, 0, 1, 1, 0, 0, 0),
+            targets_atom: intern(display, "TARGETS"),
+            targets: targets.iter().map(|target| intern(display, target)).collect(),
+            data,
+        };
+        xlib::XSetSelectionOwner(display, selection, owner.window, xlib::CURRENT_TIME);
+        if xlib::XGetSelectionOwner(display, selection) == owner.window {
+            Some(owner)
+        } else {
+            None
+        }
+    }
+
+    /// Store the requested target on the requestor and notify it.
+    pub unsafe fn answer(&self, request: &xlib::XSelectionRequestEvent) {
+        let display = self.display;
+        // obsolete clients leave the property out and expect the target
+        let mut property = if request.property == xlib::NONE {
+            request.target
+        } else {
+            request.property
+        };
+        if request.target == self.targets_atom {
+            let mut supported = vec![self.targets_atom];
+            supported.extend_from_slice(&self.targets);
+            xlib::XChangeProperty(
+                display,
+                request.requestor,
+                property,
+                xlib::XA_ATOM,
+                32,
+                xlib::PROP_MODE_REPLACE,
+                supported.as_ptr() as *const c_uchar,
+                supported.len() as c_int,
+            );
+        } else if self.targets.contains(&request.target) {
+            // TEXT is a request for any text type, answer with UTF-8
+            let type_ = if request.target == intern(display, "TEXT") {
+                intern(display, "UTF8_STRING")
+            } else {
+                request.target
+            };
+            xlib::XChangeProperty(
+                display,
+                request.requestor,
+                property,
+                type_,
+                8,
+                xlib::PROP_MODE_REPLACE,
+                self.data.as_ptr(),
+                self.data.len() as c_int,
+            );
+        } else {
+            property = xlib::NONE;
+        }
+
+        let mut reply: xlib::XEvent = mem::zeroed();
+        reply.selection = xlib::XSelectionEvent {
+            type_: xlib::SELECTION_NOTIFY,
+            serial: 0,
+            send_event: xlib::TRUE,
+            display,
+            requestor: request.requestor,
+            selection: request.selection,
+            target: request.target,
+            property,
+            time: request.time,
+        };
+        xlib::XSendEvent(display, request.requestor, xlib::FALSE, 0, &mut reply);
+        xlib::XFlush(display);
+    }
+}
+
+impl Drop for Owner {
+    fn drop(&mut self) {
+        unsafe {
+            xlib::XDestroyWindow(self.display, self.window);
+        }
+    }
+}
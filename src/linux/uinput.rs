@@ -0,0 +1,334 @@
+//! Virtual input devices through the kernel's uinput module: a multi-touch
+//! screen or touchpad, because neither XTest nor XInput2 let clients inject
+//! touch or gesture events, and a keyboard with a mouse for injecting below
+//! the display server.
+
+use libc::{c_char, c_int, c_ulong, c_void};
+use std::{ffi::CString, io, mem, thread, time::Duration};
+
+const UI_SET_EVBIT: c_ulong = 0x4004_5564;
+const UI_SET_KEYBIT: c_ulong = 0x4004_5565;
+const UI_SET_RELBIT: c_ulong = 0x4004_5566;
+const UI_SET_ABSBIT: c_ulong = 0x4004_5567;
+const UI_SET_PROPBIT: c_ulong = 0x4004_556e;
+const UI_DEV_CREATE: c_ulong = 0x5501;
+const UI_DEV_DESTROY: c_ulong = 0x5502;
+
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+const EV_ABS: u16 = 0x03;
+const SYN_REPORT: u16 = 0;
+/// The evdev code of the left shift key.
+pub const KEY_LEFTSHIFT: u16 = 42;
+const BTN_LEFT: u16 = 0x110;
+// BTN_RIGHT, BTN_MIDDLE, BTN_SIDE and BTN_EXTRA follow BTN_LEFT
+const BTN_EXTRA: u16 = 0x114;
+// the keyboard keys from KEY_ESC to KEY_MICMUTE
+const KEYS: std::ops::RangeInclusive<u16> = 1..=248;
+const BTN_TOUCH: u16 = 0x14a;
+const BTN_TOOL_FINGER: u16 = 0x145;
+const BTN_TOOL_QUINTTAP: u16 = 0x148;
+const BTN_TOOL_DOUBLETAP: u16 = 0x14d;
+const BTN_TOOL_TRIPLETAP: u16 = 0x14e;
+const BTN_TOOL_QUADTAP: u16 = 0x14f;
+const REL_HWHEEL: u16 = 0x06;
+const REL_WHEEL: u16 = 0x08;
+const ABS_X: u16 = 0x00;
+const ABS_Y: u16 = 0x01;
+const ABS_MT_SLOT: u16 = 0x2f;
+const ABS_MT_POSITION_X: u16 = 0x35;
+const ABS_MT_POSITION_Y: u16 = 0x36;
+const ABS_MT_TRACKING_ID: u16 = 0x39;
+const INPUT_PROP_POINTER: c_int = 0x00;
+const INPUT_PROP_DIRECT: c_int = 0x01;
+const BUS_VIRTUAL: u16 = 0x06;
+const ABS_CNT: usize = 0x40;
+
+// number of simultaneous contacts the device reports
+const SLOTS: i32 = 10;
+// time for the X server to pick up a new device
+const HOTPLUG_DELAY: Duration = Duration::from_millis(300);
+
+#[repr(C)]
+struct InputEvent {
+    time: libc::timeval,
+    type_: u16,
+    code: u16,
+    value: i32,
+}
+
+#[repr(C)]
+struct UinputUserDev {
+    name: [c_char; 80],
+    bustype: u16,
+    vendor: u16,
+    product: u16,
+    version: u16,
+    ff_effects_max: u32,
+    absmax: [i32; ABS_CNT],
+    absmin: [i32; ABS_CNT],
+    absfuzz: [i32; ABS_CNT],
+    absflat: [i32; ABS_CNT],
+}
+
+/// A touch screen covering `width`x`height` pixels of the X screen, or a
+/// touchpad of `width`x`height` units whose multi-finger swipes the desktop
+/// turns into gestures.
+pub struct TouchDevice {
+    device: Device,
+    next_tracking_id: i32,
+    touchpad: bool,
+}
+
+impl TouchDevice {
+    /// Create a touch screen. This needs write access to `/dev/uinput`.
+    pub fn new(width: i32, height: i32) -> io::Result<Self> {
+        Self::create(width, height, false)
+    }
+
+    /// Create a touchpad. This needs write access to `/dev/uinput`.
+    pub fn touchpad(width: i32, height: i32) -> io::Result<Self> {
+        Self::create(width, height, true)
+    }
+
+    fn create(width: i32, height: i32, touchpad: bool) -> io::Result<Self> {
+        let device = Device::open()?;
+        device.ioctl(UI_SET_EVBIT, c_int::from(EV_KEY))?;
+        device.ioctl(UI_SET_KEYBIT, c_int::from(BTN_TOUCH))?;
+        if touchpad {
+            // libinput only takes a device with a button and finger counts
+            // for a touchpad
+            for &code in &[
+                BTN_LEFT,
+                BTN_TOOL_FINGER,
+                BTN_TOOL_DOUBLETAP,
+                BTN_TOOL_TRIPLETAP,
+                BTN_TOOL_QUADTAP,
+                BTN_TOOL_QUINTTAP,
+            ] {
+                device.ioctl(UI_SET_KEYBIT, c_int::from(code))?;
+            }
+        }
+        device.ioctl(UI_SET_EVBIT, c_int::from(EV_ABS))?;
+        for &code in &[ABS_X, ABS_Y, ABS_MT_SLOT, ABS_MT_POSITION_X, ABS_MT_POSITION_Y, ABS_MT_TRACKING_ID] {
+            device.ioctl(UI_SET_ABSBIT, c_int::from(code))?;
+        }
+        device.ioctl(UI_SET_PROPBIT, if touchpad { INPUT_PROP_POINTER } else { INPUT_PROP_DIRECT })?;
+
+        let name: &[u8] = if touchpad { b"enigo touchpad" } else { b"enigo touch" };
+        let mut setup = UinputUserDev::named(name);
+        for &(code, max) in &[
+            (ABS_X, width - 1),
+            (ABS_Y, height - 1),
+            (ABS_MT_POSITION_X, width - 1),
+            (ABS_MT_POSITION_Y, height - 1),
+            (ABS_MT_SLOT, SLOTS - 1),
+            (ABS_MT_TRACKING_ID, i32::from(u16::MAX)),
+        ] {
+            setup.absmax[usize::from(code)] = max;
+        }
+        device.create(&setup)?;
+        Ok(TouchDevice {
+            device,
+            next_tracking_id: 0,
+            touchpad,
+        })
+    }
+
+    /// Put one finger per point on the screen, in slots 0, 1, …
+    pub fn touch_down(&mut self, points: &[(i32, i32)]) -> io::Result<()> {
+        for (slot, &(x, y)) in points.iter().enumerate() {
+            let id = self.next_tracking_id;
+            self.next_tracking_id = (id + 1) % i32::from(u16::MAX);
+            self.device.event(EV_ABS, ABS_MT_SLOT, slot as i32)?;
+            self.device.event(EV_ABS, ABS_MT_TRACKING_ID, id)?;
+            self.position(x, y)?;
+        }
+        if let Some(&(x, y)) = points.first() {
+            self.device.event(EV_KEY, BTN_TOUCH, 1)?;
+            self.finger_count(points.len(), 1)?;
+            self.device.event(EV_ABS, ABS_X, x)?;
+            self.device.event(EV_ABS, ABS_Y, y)?;
+        }
+        self.device.event(EV_SYN, SYN_REPORT, 0)
+    }
+
+    /// Move the fingers put down by [touch_down](#method.touch_down).
+    pub fn touch_move(&mut self, points: &[(i32, i32)]) -> io::Result<()> {
+        for (slot, &(x, y)) in points.iter().enumerate() {
+            self.device.event(EV_ABS, ABS_MT_SLOT, slot as i32)?;
+            self.position(x, y)?;
+        }
+        if let Some(&(x, y)) = points.first() {
+            self.device.event(EV_ABS, ABS_X, x)?;
+            self.device.event(EV_ABS, ABS_Y, y)?;
+        }
+        self.device.event(EV_SYN, SYN_REPORT, 0)
+    }
+
+    /// Lift the fingers in the first `count` slots.
+    pub fn touch_up(&mut self, count: usize) -> io::Result<()> {
+        for slot in 0..count {
+            self.device.event(EV_ABS, ABS_MT_SLOT, slot as i32)?;
+            self.device.event(EV_ABS, ABS_MT_TRACKING_ID, -1)?;
+        }
+        self.device.event(EV_KEY, BTN_TOUCH, 0)?;
+        self.finger_count(count, 0)?;
+        self.device.event(EV_SYN, SYN_REPORT, 0)
+    }
+
+    // A touchpad also reports how many fingers are down through a tool key
+    fn finger_count(&self, count: usize, value: i32) -> io::Result<()> {
+        let tool = match count {
+            _ if !self.touchpad => return Ok(()),
+            0 => return Ok(()),
+            1 => BTN_TOOL_FINGER,
+            2 => BTN_TOOL_DOUBLETAP,
+            3 => BTN_TOOL_TRIPLETAP,
+            4 => BTN_TOOL_QUADTAP,
+            _ => BTN_TOOL_QUINTTAP,
+        };
+        self.device.event(EV_KEY, tool, value)
+    }
+
+    fn position(&self, x: i32, y: i32) -> io::Result<()> {
+        self.device.event(EV_ABS, ABS_MT_POSITION_X, x)?;
+        self.device.event(EV_ABS, ABS_MT_POSITION_Y, y)
+    }
+}
+
+/// A keyboard with an absolute pointer of `width`x`height` pixels, two
+/// wheels and five buttons, like the tablet of a virtual machine.
+pub struct VirtualInput {
+    device: Device,
+}
+
+impl VirtualInput {
+    /// Create the device. This needs write access to `/dev/uinput`.
+    pub fn new(width: i32, height: i32) -> io::Result<Self> {
+        let device = Device::open()?;
+        device.ioctl(UI_SET_EVBIT, c_int::from(EV_KEY))?;
+        for code in KEYS.chain(BTN_LEFT..=BTN_EXTRA) {
+            device.ioctl(UI_SET_KEYBIT, c_int::from(code))?;
+        }
+        device.ioctl(UI_SET_EVBIT, c_int::from(EV_REL))?;
+        device.ioctl(UI_SET_RELBIT, c_int::from(REL_WHEEL))?;
+        device.ioctl(UI_SET_RELBIT, c_int::from(REL_HWHEEL))?;
+        device.ioctl(UI_SET_EVBIT, c_int::from(EV_ABS))?;
+        device.ioctl(UI_SET_ABSBIT, c_int::from(ABS_X))?;
+        device.ioctl(UI_SET_ABSBIT, c_int::from(ABS_Y))?;
+
+        let mut setup = UinputUserDev::named(b"enigo input");
+        setup.absmax[usize::from(ABS_X)] = width - 1;
+        setup.absmax[usize::from(ABS_Y)] = height - 1;
+        device.create(&setup)?;
+        Ok(VirtualInput { device })
+    }
+
+    /// Press or release the key with the evdev code `code`, e.g. 30 for
+    /// `KEY_A`.
+    pub fn key(&mut self, code: u16, down: bool) -> io::Result<()> {
+        self.device.event(EV_KEY, code, i32::from(down))?;
+        self.device.event(EV_SYN, SYN_REPORT, 0)
+    }
+
+    /// Press or release button `button`, counting from 0 for the left one
+    /// over right, middle and back to 4 for forward.
+    pub fn button(&mut self, button: u16, down: bool) -> io::Result<()> {
+        self.key(BTN_LEFT + button.min(BTN_EXTRA - BTN_LEFT), down)
+    }
+
+    /// Move the pointer to `x`, `y`.
+    pub fn move_to(&mut self, x: i32, y: i32) -> io::Result<()> {
+        self.device.event(EV_ABS, ABS_X, x)?;
+        self.device.event(EV_ABS, ABS_Y, y)?;
+        self.device.event(EV_SYN, SYN_REPORT, 0)
+    }
+
+    /// Turn the wheels by `vertical` notches up and `horizontal` notches
+    /// right, negative values turning them down and left.
+    pub fn scroll(&mut self, vertical: i32, horizontal: i32) -> io::Result<()> {
+        if vertical != 0 {
+            self.device.event(EV_REL, REL_WHEEL, vertical)?;
+        }
+        if horizontal != 0 {
+            self.device.event(EV_REL, REL_HWHEEL, horizontal)?;
+        }
+        self.device.event(EV_SYN, SYN_REPORT, 0)
+    }
+}
+
+impl UinputUserDev {
+    fn named(name: &[u8]) -> Self {
+        let mut setup: UinputUserDev = unsafe { mem::zeroed() };
+        for (byte, name) in setup.name.iter_mut().zip(name.iter()) {
+            *byte = *name as c_char;
+        }
+        setup.bustype = BUS_VIRTUAL;
+        setup.version = 1;
+        setup
+    }
+}
+
+// An open /dev/uinput, destroying its device when dropped
+struct Device {
+    fd: c_int,
+}
+
+impl Device {
+    fn open() -> io::Result<Self> {
+        let path = CString::new("/dev/uinput").unwrap();
+        let fd = unsafe { libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_NONBLOCK) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Device { fd })
+    }
+
+    fn create(&self, setup: &UinputUserDev) -> io::Result<()> {
+        self.write(setup)?;
+        self.ioctl(UI_DEV_CREATE, 0)?;
+        thread::sleep(HOTPLUG_DELAY);
+        Ok(())
+    }
+
+    fn event(&self, type_: u16, code: u16, value: i32) -> io::Result<()> {
+        self.write(&InputEvent {
+            time: libc::timeval {
+                tv_sec: 0,
+                tv_usec: 0,
+            },
+            type_,
+            code,
+            value,
+        })
+    }
+
+    fn write<T>(&self, data: &T) -> io::Result<()> {
+        let size = mem::size_of::<T>();
+        let written = unsafe { libc::write(self.fd, data as *const T as *const c_void, size) };
+        if written == size as isize {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    fn ioctl(&self, request: c_ulong, value: c_int) -> io::Result<()> {
+        if unsafe { libc::ioctl(self.fd, request as _, value) } < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        unsafe {
+            libc::ioctl(self.fd, UI_DEV_DESTROY as _);
+            libc::close(self.fd);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! The source side of the XDND drag-and-drop protocol, see
+//! <https://freedesktop.org/wiki/Specifications/XDND/>.
+
+use super::{
+    selection::{intern, Owner},
+    xerror, xlib,
+};
+use libc::{c_int, c_long, c_uchar, c_ulong};
+use std::{
+    ffi::CStr,
+    mem, ptr, thread,
+    time::{Duration, Instant},
+};
+
+const XDND_VERSION: c_long = 5;
+// how long the target may take to answer a position or to finish a drop
+const STATUS_TIMEOUT: Duration = Duration::from_secs(1);
+const FINISHED_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Drop `data` of type `mime` on `target` at the root coordinates `x`, `y`.
+/// Returns whether the target accepted and finished the drop.
+///
+/// The data is offered by a hidden window on a connection of its own to
+/// `display_name` (or `$DISPLAY`), which owns `XdndSelection` for the
+/// duration of the drag.
+pub fn drop_on(
+    display_name: Option<&CStr>,
+    target: xlib::Window,
+    x: i32,
+    y: i32,
+    data: &[u8],
+    mime: &str,
+) -> bool {
+    unsafe {
+        let display = xlib::XOpenDisplay(display_name.map_or(ptr::null(), CStr::as_ptr));
+        if display.is_null() {
+            return false;
+        }
+        let dropped = run(display, target, x, y, data, mime);
+        xlib::XCloseDisplay(display);
+        dropped
+    }
+}
+
+unsafe fn run(
+    display: *mut xlib::Display,
+    target: xlib::Window,
+    x: i32,
+    y: i32,
+    data: &[u8],
+    mime: &str,
+) -> bool {
+    let version = match aware_version(display, target) {
+        Some(version) => version.min(XDND_VERSION),
+        None => return false,
+    };
+    let owner = match Owner::new(display, "XdndSelection", &[mime], data.to_vec()) {
+        Some(owner) => owner,
+        None => return false,
+    };
+    let source = owner.window as c_long;
+    // false once the target is gone
+    let send = |message: &str, data: [c_long; 5]| {
+        let mut event: xlib::XEvent = mem::zeroed();
+        event.client_message = xlib::XClientMessageEvent {
+            type_: xlib::CLIENT_MESSAGE,
+            serial: 0,
+            send_event: xlib::TRUE,
+            display,
+            window: target,
+            message_type: intern(display, message),
+            format: 32,
+            data,
+        };
+        let (_, error) = xerror::trap(display, || {
+            xlib::XSendEvent(display, target, xlib::FALSE, xlib::NO_EVENT_MASK, &mut event)
+        });
+        error.is_none()
+    };
+
+    let mime = intern(display, mime) as c_long;
+    let position = (c_long::from(x) << 16) | (c_long::from(y) & 0xffff);
+    let copy = intern(display, "XdndActionCopy") as c_long;
+    if !send("XdndEnter", [source, version << 24, mime, 0, 0])
+        || !send("XdndPosition", [source, 0, position, xlib::CURRENT_TIME as c_long, copy])
+    {
+        return false;
+    }
+
+    let status = intern(display, "XdndStatus");
+    let mut accepted = false;
+    wait(display, &owner, STATUS_TIMEOUT, |message| {
+        if message.message_type == status {
+            accepted = message.data[1] & 1 != 0;
+            return true;
+        }
+        false
+    });
+    if !accepted {
+        send("XdndLeave", [source, 0, 0, 0, 0]);
+        return false;
+    }
+
+    if !send("XdndDrop", [source, 0, xlib::CURRENT_TIME as c_long, 0, 0]) {
+        return false;
+    }
+    let finished = intern(display, "XdndFinished");
+    // targets older than version 2 don't send XdndFinished
+    let mut success = version < 2;
+    wait(display, &owner, FINISHED_TIMEOUT, |message| {
+        if message.message_type == finished {
+            // before version 5 XdndFinished doesn't say whether the drop worked
+            success = version < 5 || message.data[1] & 1 != 0;
+            return true;
+        }
+        false
+    });
+    success
+}
+
+// Answer selection requests and hand client messages to `done` until it
+// returns true or `timeout` passed
+unsafe fn wait(
+    display: *mut xlib::Display,
+    owner: &Owner,
+    timeout: Duration,
+    mut done: impl FnMut(&xlib::XClientMessageEvent) -> bool,
+) {
+    let deadline = Instant::now() + timeout;
+    let mut event: xlib::XEvent = mem::zeroed();
+    while Instant::now() < deadline {
+        if xlib::XPending(display) == 0 {
+            thread::sleep(Duration::from_millis(1));
+            continue;
+        }
+        xlib::XNextEvent(display, &mut event);
+        match event.type_ {
+            xlib::SELECTION_REQUEST => owner.answer(&event.selection_request),
+            xlib::CLIENT_MESSAGE if done(&event.client_message) => return,
+            _ => {}
+        }
+    }
+}
+
+// The XDND version in the XdndAware property of `window`
+unsafe fn aware_version(display: *mut xlib::Display, window: xlib::Window) -> Option<c_long> {
+    let (mut actual_type, mut format, mut nitems, mut bytes_after) = (0, 0 as c_int, 0 as c_ulong, 0);
+    let mut data: *mut c_uchar = ptr::null_mut();
+    let property = intern(display, "XdndAware");
+    let (status, error) = xerror::trap(display, || {
+        xlib::XGetWindowProperty(
+            display,
+            window,
+            property,
+            0,
+            1,
+            xlib::FALSE,
+            xlib::XA_ATOM,
+            &mut actual_type,
+            &mut format,
+            &mut nitems,
+            &mut bytes_after,
+            &mut data,
+        )
+    });
+    if error.is_some() || status != xlib::SUCCESS || data.is_null() {
+        return None;
+    }
+    let version = if format == 32 && nitems > 0 {
+        Some(*(data as *const c_long))
+    } else {
+        None
+    };
+    xlib::XFree(data as *mut libc::c_void);
+    version
+}
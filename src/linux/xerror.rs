@@ -0,0 +1,96 @@
+//! Catching the X errors caused by enigo's own requests, which Xlib's
+//! default error handler would answer by exiting the process.
+
+use super::xlib;
+use libc::c_int;
+use std::{
+    cell::Cell,
+    sync::{Mutex, PoisonError},
+};
+
+// The error handler is installed while any thread has a trap armed and the
+// one it replaced is put back afterwards
+struct Installed {
+    armed: usize,
+    previous: xlib::XErrorHandler,
+}
+
+static INSTALLED: Mutex<Installed> = Mutex::new(Installed {
+    armed: 0,
+    previous: None,
+});
+
+thread_local! {
+    // whether errors on this thread are trapped, and the first one caught
+    static ARMED: Cell<bool> = const { Cell::new(false) };
+    static CAUGHT: Cell<Option<(u8, u32)>> = const { Cell::new(None) };
+}
+
+/// Run `call` on `display` and wait for the X server to process its
+/// requests. Returns the result of `call` along with the code and resource
+/// of the first X error it caused, if any.
+///
+/// The trap's error handler is only installed while `call` runs, so errors
+/// outside of it, e.g. of the application's own Xlib requests, go to the
+/// application's handler even if it was installed after enigo's first
+/// request. Xlib has a single error handler per process, so a handler the
+/// application installs from another thread while a trap is armed is
+/// replaced again when the trap ends.
+pub fn trap<T>(display: *mut xlib::Display, call: impl FnOnce() -> T) -> (T, Option<(u8, u32)>) {
+    if display.is_null() {
+        return (call(), None);
+    }
+    // a nested trap reports its own errors and leaves the handler to the
+    // outer one
+    let nested = ARMED.with(|armed| armed.replace(true));
+    let outer = CAUGHT.with(Cell::take);
+    if !nested {
+        install();
+    }
+    let result = call();
+    unsafe {
+        xlib::XSync(display, xlib::FALSE);
+    }
+    if !nested {
+        ARMED.with(|armed| armed.set(false));
+        uninstall();
+    }
+    (result, CAUGHT.with(|caught| caught.replace(outer)))
+}
+
+fn install() {
+    let mut installed = INSTALLED.lock().unwrap_or_else(PoisonError::into_inner);
+    if installed.armed == 0 {
+        installed.previous = unsafe { xlib::XSetErrorHandler(Some(handler)) };
+    }
+    installed.armed += 1;
+}
+
+fn uninstall() {
+    let mut installed = INSTALLED.lock().unwrap_or_else(PoisonError::into_inner);
+    installed.armed -= 1;
+    if installed.armed == 0 {
+        unsafe {
+            xlib::XSetErrorHandler(installed.previous);
+        }
+    }
+}
+
+unsafe extern "C" fn handler(display: *mut xlib::Display, event: *mut xlib::XErrorEvent) -> c_int {
+    if ARMED.with(Cell::get) {
+        let event = &*event;
+        CAUGHT.with(|caught| {
+            if caught.get().is_none() {
+                // resource IDs only have 29 bits
+                caught.set(Some((event.error_code, event.resourceid as u32)));
+            }
+        });
+        return 0;
+    }
+    // an error of another thread while this one is armed
+    let previous = INSTALLED.lock().unwrap_or_else(PoisonError::into_inner).previous;
+    match previous {
+        Some(previous) => previous(display, event),
+        None => 0,
+    }
+}
@@ -0,0 +1,486 @@
+//! Minimal bindings to the parts of Xlib that libxdo doesn't wrap.
+
+use libc::{c_char, c_int, c_long, c_short, c_uchar, c_uint, c_ulong, c_ushort, c_void};
+
+pub enum Display {}
+pub type Atom = c_ulong;
+pub type Bool = c_int;
+pub type Status = c_int;
+pub type Time = c_ulong;
+pub type Window = c_ulong;
+pub type KeySym = c_ulong;
+pub type KeyCode = c_uchar;
+
+pub const SUCCESS: Status = 0;
+pub const FALSE: Bool = 0;
+pub const TRUE: Bool = 1;
+pub const CURRENT_TIME: Time = 0;
+pub const NONE: Atom = 0;
+
+pub const XA_ATOM: Atom = 4;
+pub const PROP_MODE_REPLACE: c_int = 0;
+pub const PROP_MODE_APPEND: c_int = 2;
+
+pub const BUTTON_PRESS: c_int = 4;
+pub const BUTTON_RELEASE: c_int = 5;
+pub const SELECTION_CLEAR: c_int = 29;
+pub const SELECTION_REQUEST: c_int = 30;
+pub const SELECTION_NOTIFY: c_int = 31;
+pub const CLIENT_MESSAGE: c_int = 33;
+pub const GENERIC_EVENT: c_int = 35;
+pub const NO_EVENT_MASK: c_long = 0;
+pub const BUTTON_PRESS_MASK: c_long = 1 << 2;
+pub const BUTTON_RELEASE_MASK: c_long = 1 << 3;
+pub const SUBSTRUCTURE_NOTIFY_MASK: c_long = 1 << 19;
+pub const SUBSTRUCTURE_REDIRECT_MASK: c_long = 1 << 20;
+pub const PROPERTY_CHANGE_MASK: c_long = 1 << 22;
+
+pub const ANY_PROPERTY_TYPE: Atom = 0;
+
+pub const IS_VIEWABLE: c_int = 2;
+pub const INPUT_HINT: c_long = 1 << 0;
+
+pub const XI_ALL_DEVICES: c_int = 0;
+pub const XI_MASTER_POINTER: c_int = 1;
+pub const XI_MASTER_KEYBOARD: c_int = 2;
+pub const XI_SLAVE_POINTER: c_int = 3;
+pub const XI_SLAVE_KEYBOARD: c_int = 4;
+pub const XI_ALL_MASTER_DEVICES: c_int = 1;
+pub const XI_RAW_KEY_PRESS: c_int = 13;
+pub const XI_RAW_KEY_RELEASE: c_int = 14;
+pub const XI_RAW_BUTTON_PRESS: c_int = 15;
+pub const XI_RAW_BUTTON_RELEASE: c_int = 16;
+pub const XI_RAW_MOTION: c_int = 17;
+
+pub const XKB_USE_CORE_KBD: c_uint = 0x0100;
+pub const XKB_KEY_NAMES_MASK: c_uint = 1 << 9;
+pub const XKB_GROUP_NAMES_MASK: c_uint = 1 << 12;
+pub const XKB_ALL_COMPONENTS_MASK: c_uint = 0x7f;
+pub const XKB_GBN_ALL_COMPONENTS_MASK: c_uint = 0xff;
+pub const XKB_GBN_GEOMETRY_MASK: c_uint = 1 << 6;
+
+#[repr(C)]
+pub struct XkbNamesRec {
+    pub keycodes: Atom,
+    pub geometry: Atom,
+    pub symbols: Atom,
+    pub types: Atom,
+    pub compat: Atom,
+    pub vmods: [Atom; 16],
+    pub indicators: [Atom; 32],
+    pub groups: [Atom; 4],
+    pub keys: *mut [c_char; 4],
+    pub key_aliases: *mut c_void,
+    pub radio_groups: *mut Atom,
+    pub phys_symbols: Atom,
+    pub num_keys: c_uchar,
+    pub num_key_aliases: c_uchar,
+    pub num_rg: c_ushort,
+}
+
+#[repr(C)]
+pub struct XkbDescRec {
+    pub display: *mut Display,
+    pub flags: c_ushort,
+    pub device_spec: c_ushort,
+    pub min_key_code: c_uchar,
+    pub max_key_code: c_uchar,
+    pub ctrls: *mut c_void,
+    pub server: *mut c_void,
+    pub map: *mut c_void,
+    pub indicators: *mut c_void,
+    pub names: *mut XkbNamesRec,
+    pub compat: *mut c_void,
+    pub geom: *mut c_void,
+}
+
+#[repr(C)]
+pub struct XkbComponentNamesRec {
+    pub keymap: *mut c_char,
+    pub keycodes: *mut c_char,
+    pub types: *mut c_char,
+    pub compat: *mut c_char,
+    pub symbols: *mut c_char,
+    pub geometry: *mut c_char,
+}
+
+#[repr(C)]
+#[derive(Default)]
+pub struct XkbStateRec {
+    pub group: c_uchar,
+    pub locked_group: c_uchar,
+    pub base_group: c_ushort,
+    pub latched_group: c_ushort,
+    pub mods: c_uchar,
+    pub base_mods: c_uchar,
+    pub latched_mods: c_uchar,
+    pub locked_mods: c_uchar,
+    pub compat_state: c_uchar,
+    pub grab_mods: c_uchar,
+    pub compat_grab_mods: c_uchar,
+    pub lookup_mods: c_uchar,
+    pub compat_lookup_mods: c_uchar,
+    pub ptr_buttons: c_ushort,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct XButtonEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub button: c_uint,
+    pub same_screen: Bool,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct XClientMessageEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub message_type: Atom,
+    pub format: c_int,
+    pub data: [c_long; 5],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct XPropertyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub atom: Atom,
+    pub time: Time,
+    pub state: c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct XSelectionRequestEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub owner: Window,
+    pub requestor: Window,
+    pub selection: Atom,
+    pub target: Atom,
+    pub property: Atom,
+    pub time: Time,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct XSelectionEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub requestor: Window,
+    pub selection: Atom,
+    pub target: Atom,
+    pub property: Atom,
+    pub time: Time,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct XGenericEventCookie {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub cookie: c_uint,
+    pub data: *mut c_void,
+}
+
+#[repr(C)]
+pub union XEvent {
+    pub type_: c_int,
+    pub button: XButtonEvent,
+    pub client_message: XClientMessageEvent,
+    pub property: XPropertyEvent,
+    pub selection_request: XSelectionRequestEvent,
+    pub selection: XSelectionEvent,
+    pub generic: XGenericEventCookie,
+    pad: [c_long; 24],
+}
+
+#[repr(C)]
+pub struct XErrorEvent {
+    pub type_: c_int,
+    pub display: *mut Display,
+    pub resourceid: c_ulong,
+    pub serial: c_ulong,
+    pub error_code: c_uchar,
+    pub request_code: c_uchar,
+    pub minor_code: c_uchar,
+}
+
+pub type XErrorHandler = Option<unsafe extern "C" fn(*mut Display, *mut XErrorEvent) -> c_int>;
+
+#[repr(C)]
+pub struct XWindowAttributes {
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub border_width: c_int,
+    pub depth: c_int,
+    pub visual: *mut c_void,
+    pub root: Window,
+    pub class: c_int,
+    pub bit_gravity: c_int,
+    pub win_gravity: c_int,
+    pub backing_store: c_int,
+    pub backing_planes: c_ulong,
+    pub backing_pixel: c_ulong,
+    pub save_under: Bool,
+    pub colormap: c_ulong,
+    pub map_installed: Bool,
+    pub map_state: c_int,
+    pub all_event_masks: c_long,
+    pub your_event_mask: c_long,
+    pub do_not_propagate_mask: c_long,
+    pub override_redirect: Bool,
+    pub screen: *mut c_void,
+}
+
+#[repr(C)]
+pub struct XWMHints {
+    pub flags: c_long,
+    pub input: Bool,
+    pub initial_state: c_int,
+    pub icon_pixmap: c_ulong,
+    pub icon_window: Window,
+    pub icon_x: c_int,
+    pub icon_y: c_int,
+    pub icon_mask: c_ulong,
+    pub window_group: c_ulong,
+}
+
+#[repr(C)]
+pub struct XClassHint {
+    pub res_name: *mut c_char,
+    pub res_class: *mut c_char,
+}
+
+#[repr(C)]
+pub struct XFixesCursorImage {
+    pub x: c_short,
+    pub y: c_short,
+    pub width: c_ushort,
+    pub height: c_ushort,
+    pub xhot: c_ushort,
+    pub yhot: c_ushort,
+    pub cursor_serial: c_ulong,
+    pub pixels: *mut c_ulong,
+    pub atom: Atom,
+    pub name: *const c_char,
+}
+
+#[repr(C)]
+pub struct XIEventMask {
+    pub deviceid: c_int,
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+}
+
+#[repr(C)]
+pub struct XIDeviceInfo {
+    pub deviceid: c_int,
+    pub name: *mut c_char,
+    pub use_: c_int,
+    pub attachment: c_int,
+    pub enabled: Bool,
+    pub num_classes: c_int,
+    pub classes: *mut *mut c_void,
+}
+
+#[repr(C)]
+pub struct XRRMonitorInfo {
+    pub name: Atom,
+    pub primary: Bool,
+    pub automatic: Bool,
+    pub noutput: c_int,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub mwidth: c_int,
+    pub mheight: c_int,
+    pub outputs: *mut c_ulong,
+}
+
+#[link(name = "X11")]
+extern "C" {
+    pub fn XOpenDisplay(name: *const c_char) -> *mut Display;
+    pub fn XCloseDisplay(display: *mut Display) -> c_int;
+    pub fn XDisplayString(display: *mut Display) -> *mut c_char;
+    pub fn XFree(data: *mut c_void) -> c_int;
+    pub fn XQueryExtension(
+        display: *mut Display,
+        name: *const c_char,
+        major_opcode: *mut c_int,
+        first_event: *mut c_int,
+        first_error: *mut c_int,
+    ) -> Bool;
+    pub fn XDefaultRootWindow(display: *mut Display) -> Window;
+    pub fn XDefaultScreen(display: *mut Display) -> c_int;
+    pub fn XScreenCount(display: *mut Display) -> c_int;
+    pub fn XGetAtomName(display: *mut Display, atom: Atom) -> *mut c_char;
+    pub fn XSendEvent(
+        display: *mut Display,
+        window: Window,
+        propagate: Bool,
+        event_mask: c_long,
+        event: *mut XEvent,
+    ) -> Status;
+    pub fn XFlush(display: *mut Display) -> c_int;
+    pub fn XNextEvent(display: *mut Display, event: *mut XEvent) -> c_int;
+    pub fn XPending(display: *mut Display) -> c_int;
+    pub fn XWindowEvent(display: *mut Display, window: Window, event_mask: c_long, event: *mut XEvent) -> c_int;
+    pub fn XSelectInput(display: *mut Display, window: Window, event_mask: c_long) -> c_int;
+    pub fn XSync(display: *mut Display, discard: Bool) -> c_int;
+    pub fn XGrabServer(display: *mut Display) -> c_int;
+    pub fn XUngrabServer(display: *mut Display) -> c_int;
+    pub fn XSetErrorHandler(handler: XErrorHandler) -> XErrorHandler;
+    pub fn XCreateSimpleWindow(
+        display: *mut Display,
+        parent: Window,
+        x: c_int,
+        y: c_int,
+        width: c_uint,
+        height: c_uint,
+        border_width: c_uint,
+        border: c_ulong,
+        background: c_ulong,
+    ) -> Window;
+    pub fn XDestroyWindow(display: *mut Display, window: Window) -> c_int;
+    pub fn XSetSelectionOwner(display: *mut Display, selection: Atom, owner: Window, time: Time) -> c_int;
+    pub fn XGetSelectionOwner(display: *mut Display, selection: Atom) -> Window;
+    pub fn XChangeProperty(
+        display: *mut Display,
+        window: Window,
+        property: Atom,
+        type_: Atom,
+        format: c_int,
+        mode: c_int,
+        data: *const c_uchar,
+        nelements: c_int,
+    ) -> c_int;
+    pub fn XInternAtom(display: *mut Display, name: *const c_char, only_if_exists: Bool) -> Atom;
+    pub fn XStringToKeysym(string: *const c_char) -> KeySym;
+    pub fn XKeysymToKeycode(display: *mut Display, keysym: KeySym) -> KeyCode;
+    pub fn XkbKeycodeToKeysym(display: *mut Display, keycode: KeyCode, group: c_int, level: c_int) -> KeySym;
+    pub fn XGetWindowAttributes(
+        display: *mut Display,
+        window: Window,
+        attributes: *mut XWindowAttributes,
+    ) -> Status;
+    pub fn XGetWindowProperty(
+        display: *mut Display,
+        window: Window,
+        property: Atom,
+        long_offset: c_long,
+        long_length: c_long,
+        delete: Bool,
+        req_type: Atom,
+        actual_type: *mut Atom,
+        actual_format: *mut c_int,
+        nitems: *mut c_ulong,
+        bytes_after: *mut c_ulong,
+        prop: *mut *mut c_uchar,
+    ) -> c_int;
+    pub fn XListProperties(display: *mut Display, window: Window, num_props: *mut c_int) -> *mut Atom;
+    pub fn XGetClassHint(display: *mut Display, window: Window, class_hint: *mut XClassHint) -> Status;
+    pub fn XGetWMHints(display: *mut Display, window: Window) -> *mut XWMHints;
+    pub fn XGetWMProtocols(
+        display: *mut Display,
+        window: Window,
+        protocols: *mut *mut Atom,
+        count: *mut c_int,
+    ) -> Status;
+
+    pub fn XkbGetMap(display: *mut Display, which: c_uint, device_spec: c_uint) -> *mut XkbDescRec;
+    pub fn XkbGetNames(display: *mut Display, which: c_uint, xkb: *mut XkbDescRec) -> Status;
+    pub fn XkbGetKeyboardByName(
+        display: *mut Display,
+        device_spec: c_uint,
+        names: *mut XkbComponentNamesRec,
+        want: c_uint,
+        need: c_uint,
+        load: Bool,
+    ) -> *mut XkbDescRec;
+    pub fn XkbFreeKeyboard(xkb: *mut XkbDescRec, which: c_uint, free_desc: Bool);
+    pub fn XkbGetAutoRepeatRate(
+        display: *mut Display,
+        device_spec: c_uint,
+        delay: *mut c_uint,
+        interval: *mut c_uint,
+    ) -> Bool;
+    pub fn XkbGetState(display: *mut Display, device_spec: c_uint, state: *mut XkbStateRec) -> Status;
+}
+
+#[link(name = "Xrandr")]
+extern "C" {
+    pub fn XRRGetMonitors(
+        display: *mut Display,
+        window: Window,
+        get_active: Bool,
+        nmonitors: *mut c_int,
+    ) -> *mut XRRMonitorInfo;
+    pub fn XRRFreeMonitors(monitors: *mut XRRMonitorInfo);
+}
+
+#[link(name = "Xi")]
+extern "C" {
+    pub fn XIQueryDevice(display: *mut Display, deviceid: c_int, ndevices: *mut c_int) -> *mut XIDeviceInfo;
+    pub fn XIFreeDeviceInfo(info: *mut XIDeviceInfo);
+    pub fn XIQueryVersion(display: *mut Display, major: *mut c_int, minor: *mut c_int) -> Status;
+    pub fn XISelectEvents(
+        display: *mut Display,
+        window: Window,
+        masks: *mut XIEventMask,
+        num_masks: c_int,
+    ) -> Status;
+}
+
+#[link(name = "Xss")]
+extern "C" {
+    pub fn XScreenSaverSuspend(display: *mut Display, suspend: Bool);
+}
+
+#[link(name = "Xfixes")]
+extern "C" {
+    pub fn XFixesGetCursorImage(display: *mut Display) -> *mut XFixesCursorImage;
+    pub fn XFixesCreatePointerBarrier(
+        display: *mut Display,
+        window: Window,
+        x1: c_int,
+        y1: c_int,
+        x2: c_int,
+        y2: c_int,
+        directions: c_int,
+        num_devices: c_int,
+        devices: *mut c_int,
+    ) -> c_ulong;
+    pub fn XFixesDestroyPointerBarrier(display: *mut Display, barrier: c_ulong);
+}
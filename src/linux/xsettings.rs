@@ -0,0 +1,126 @@
+//! Reading the desktop settings that the XSETTINGS manager of GNOME, Xfce
+//! and others publishes, see
+//! <https://specifications.freedesktop.org/xsettings-spec/latest/>.
+
+const TYPE_INTEGER: u8 = 0;
+const TYPE_STRING: u8 = 1;
+const TYPE_COLOR: u8 = 2;
+// byte order marker of MSB first data
+const MSB_FIRST: u8 = 1;
+
+/// Find the integer setting `name`, e.g. `Net/DoubleClickTime`, in the
+/// contents of the `_XSETTINGS_SETTINGS` property. Returns `None` if it
+/// isn't there or the data is cut short.
+pub fn integer(data: &[u8], name: &str) -> Option<i32> {
+    let mut reader = Reader {
+        data,
+        position: 0,
+        big_endian: *data.first()? == MSB_FIRST,
+    };
+    // byte order and padding, then the serial
+    reader.skip(8)?;
+    let count = reader.u32()?;
+    for _ in 0..count {
+        let type_ = reader.u8()?;
+        reader.skip(1)?;
+        let name_len = usize::from(reader.u16()?);
+        let setting = reader.bytes(name_len)?;
+        reader.skip(padding(name_len))?;
+        // serial of the last change
+        reader.skip(4)?;
+        match type_ {
+            TYPE_INTEGER => {
+                let value = reader.u32()? as i32;
+                if setting == name.as_bytes() {
+                    return Some(value);
+                }
+            }
+            TYPE_STRING => {
+                let len = reader.u32()? as usize;
+                reader.skip(len + padding(len))?;
+            }
+            TYPE_COLOR => reader.skip(8)?,
+            _ => return None,
+        }
+    }
+    None
+}
+
+// Entries are padded to multiples of four bytes
+fn padding(len: usize) -> usize {
+    (4 - len % 4) % 4
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.position..self.position.checked_add(len)?)?;
+        self.position += len;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|bytes| bytes[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let bytes = [self.u8()?, self.u8()?];
+        Some(if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = [self.u8()?, self.u8()?, self.u8()?, self.u8()?];
+        Some(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // XSETTINGS data in little endian with a string and an integer
+    fn settings() -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 0];
+        data.extend_from_slice(&7u32.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&[TYPE_STRING, 0]);
+        data.extend_from_slice(&13u16.to_le_bytes());
+        data.extend_from_slice(b"Net/ThemeName\0\0\0");
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&7u32.to_le_bytes());
+        data.extend_from_slice(b"Adwaita\0");
+        data.extend_from_slice(&[TYPE_INTEGER, 0]);
+        data.extend_from_slice(&19u16.to_le_bytes());
+        data.extend_from_slice(b"Net/DoubleClickTime\0");
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&250u32.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn integer_settings() {
+        let data = settings();
+        assert_eq!(integer(&data, "Net/DoubleClickTime"), Some(250));
+        assert_eq!(integer(&data, "Net/ThemeName"), None);
+        assert_eq!(integer(&data, "Net/CursorBlinkTime"), None);
+        assert_eq!(integer(&data[..data.len() - 2], "Net/DoubleClickTime"), None);
+        assert_eq!(integer(&[], "Net/DoubleClickTime"), None);
+    }
+}
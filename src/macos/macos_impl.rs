@@ -8,8 +8,8 @@ use self::core_graphics::event_source::*;
 
 use crate::macos::keycodes::*;
 use crate::{Key, KeyboardControllable, MouseButton, MouseControllable};
-use objc::runtime::Class;
-use std::os::raw::*;
+use objc::runtime::{Class, Object};
+use std::{os::raw::*, ptr};
 
 // required for pressedMouseButtons on NSEvent
 #[link(name = "AppKit", kind = "framework")]
@@ -30,8 +30,27 @@ extern "C" {
         wheel1: i32,
         ...
     ) -> *mut MyCGEvent;
+    // for the button number of the side buttons, which CGMouseButton has no
+    // variants for
+    fn CGEventCreateMouseEvent(
+        source: &CGEventSourceRef,
+        mouseType: CGEventType,
+        mouseCursorPosition: CGPoint,
+        mouseButton: CGMouseButton,
+    ) -> *mut MyCGEvent;
+    fn CGEventSetIntegerValueField(event: *mut MyCGEvent, field: u32, value: i64);
 }
 
+#[allow(non_upper_case_globals)]
+const kCGMouseEventButtonNumber: u32 = 3;
+
+// the system-defined events the media keys send, from IOKit's ev_keymap.h
+const NS_EVENT_TYPE_SYSTEM_DEFINED: c_ulong = 14;
+const NX_SUBTYPE_AUX_CONTROL_BUTTONS: c_short = 8;
+const NX_KEYTYPE_PLAY: c_long = 16;
+const NX_KEYTYPE_NEXT: c_long = 17;
+const NX_KEYTYPE_PREVIOUS: c_long = 18;
+
 pub type CFDataRef = *const c_void;
 
 #[repr(C)]
@@ -271,6 +290,8 @@ impl MouseControllable for Enigo {
             MouseButton::Left => (CGMouseButton::Left, CGEventType::LeftMouseDown),
             MouseButton::Middle => (CGMouseButton::Center, CGEventType::OtherMouseDown),
             MouseButton::Right => (CGMouseButton::Right, CGEventType::RightMouseDown),
+            MouseButton::Back => return self.post_side_button(CGEventType::OtherMouseDown, 3),
+            MouseButton::Forward => return self.post_side_button(CGEventType::OtherMouseDown, 4),
             _ => unimplemented!(),
         };
         let dest = CGPoint::new(current_x as f64, current_y as f64);
@@ -285,6 +306,8 @@ impl MouseControllable for Enigo {
             MouseButton::Left => (CGMouseButton::Left, CGEventType::LeftMouseUp),
             MouseButton::Middle => (CGMouseButton::Center, CGEventType::OtherMouseUp),
             MouseButton::Right => (CGMouseButton::Right, CGEventType::RightMouseUp),
+            MouseButton::Back => return self.post_side_button(CGEventType::OtherMouseUp, 3),
+            MouseButton::Forward => return self.post_side_button(CGEventType::OtherMouseUp, 4),
             _ => unimplemented!(),
         };
         let dest = CGPoint::new(current_x as f64, current_y as f64);
@@ -367,36 +390,24 @@ impl KeyboardControllable for Enigo {
     }
 
     fn key_click(&mut self, key: Key) {
-        let keycode = self.key_to_keycode(key);
-
         use std::{thread, time};
         thread::sleep(time::Duration::from_millis(20));
-        let event = CGEvent::new_keyboard_event(self.event_source.clone(), keycode, true)
-            .expect("Failed creating event");
-        event.post(CGEventTapLocation::HID);
+        self.post_key(key, true);
 
         thread::sleep(time::Duration::from_millis(20));
-        let event = CGEvent::new_keyboard_event(self.event_source.clone(), keycode, false)
-            .expect("Failed creating event");
-        event.post(CGEventTapLocation::HID);
+        self.post_key(key, false);
     }
 
     fn key_down(&mut self, key: Key) {
         use std::{thread, time};
         thread::sleep(time::Duration::from_millis(20));
-        let event =
-            CGEvent::new_keyboard_event(self.event_source.clone(), self.key_to_keycode(key), true)
-                .expect("Failed creating event");
-        event.post(CGEventTapLocation::HID);
+        self.post_key(key, true);
     }
 
     fn key_up(&mut self, key: Key) {
         use std::{thread, time};
         thread::sleep(time::Duration::from_millis(20));
-        let event =
-            CGEvent::new_keyboard_event(self.event_source.clone(), self.key_to_keycode(key), false)
-                .expect("Failed creating event");
-        event.post(CGEventTapLocation::HID);
+        self.post_key(key, false);
     }
 }
 
@@ -406,6 +417,61 @@ impl Enigo {
         unsafe { msg_send![ns_event, pressedMouseButtons] }
     }
 
+    // Post a press or release of `key`. Keys without a virtual key code
+    // are left out.
+    fn post_key(&self, key: Key, down: bool) {
+        if let Some(key_type) = media_key_type(key) {
+            return Self::post_media_key(key_type, down);
+        }
+        if let Some(keycode) = self.key_to_keycode(key) {
+            let event = CGEvent::new_keyboard_event(self.event_source.clone(), keycode, down)
+                .expect("Failed creating event");
+            event.post(CGEventTapLocation::HID);
+        }
+    }
+
+    // Post a press or release of a media key the way the keyboard sends it:
+    // as an NSSystemDefined event of the auxiliary control buttons
+    fn post_media_key(key_type: c_long, down: bool) {
+        let ns_event = Class::get("NSEvent").unwrap();
+        let state: c_long = if down { 0xa } else { 0xb };
+        unsafe {
+            let event: *mut Object = msg_send![ns_event,
+                otherEventWithType: NS_EVENT_TYPE_SYSTEM_DEFINED
+                location: NSPoint { x: 0.0, y: 0.0 }
+                modifierFlags: (state << 8) as c_ulong
+                timestamp: 0.0 as c_double
+                windowNumber: 0 as c_long
+                context: ptr::null_mut::<Object>()
+                subtype: NX_SUBTYPE_AUX_CONTROL_BUTTONS
+                data1: (key_type << 16) | (state << 8)
+                data2: -1 as c_long];
+            if event.is_null() {
+                return;
+            }
+            let cg_event: *mut MyCGEvent = msg_send![event, CGEvent];
+            CGEventPost(CGEventTapLocation::HID, cg_event);
+        }
+    }
+
+    // Post a press or release of the side button `number` at the mouse
+    // location, 3 for back and 4 for forward
+    fn post_side_button(&self, event_type: CGEventType, number: i64) {
+        let (current_x, current_y) = Self::mouse_location();
+        let dest = CGPoint::new(current_x as f64, current_y as f64);
+        unsafe {
+            let event = CGEventCreateMouseEvent(
+                &self.event_source,
+                event_type,
+                dest,
+                CGMouseButton::Center,
+            );
+            CGEventSetIntegerValueField(event, kCGMouseEventButtonNumber, number);
+            CGEventPost(CGEventTapLocation::HID, event);
+            CFRelease(event as *const std::ffi::c_void);
+        }
+    }
+
     /// Fetches the `(width, height)` in pixels of the main display
     pub fn main_display_size() -> (usize, usize) {
         let display_id = unsafe { CGMainDisplayID() };
@@ -431,45 +497,68 @@ impl Enigo {
         (x, (display_height as i32) - y_inv)
     }
 
-    fn key_to_keycode(&self, key: Key) -> CGKeyCode {
+    fn key_to_keycode(&self, key: Key) -> Option<CGKeyCode> {
         #[allow(deprecated)]
         // I mean duh, we still need to support deprecated keys until they're removed
         match key {
-            Key::Alt => kVK_Option,
-            Key::Backspace => kVK_Delete,
-            Key::CapsLock => kVK_CapsLock,
-            Key::Control => kVK_Control,
-            Key::Delete => kVK_ForwardDelete,
-            Key::DownArrow => kVK_DownArrow,
-            Key::End => kVK_End,
-            Key::Escape => kVK_Escape,
-            Key::F1 => kVK_F1,
-            Key::F10 => kVK_F10,
-            Key::F11 => kVK_F11,
-            Key::F12 => kVK_F12,
-            Key::F2 => kVK_F2,
-            Key::F3 => kVK_F3,
-            Key::F4 => kVK_F4,
-            Key::F5 => kVK_F5,
-            Key::F6 => kVK_F6,
-            Key::F7 => kVK_F7,
-            Key::F8 => kVK_F8,
-            Key::F9 => kVK_F9,
-            Key::Home => kVK_Home,
-            Key::LeftArrow => kVK_LeftArrow,
-            Key::Option => kVK_Option,
-            Key::PageDown => kVK_PageDown,
-            Key::PageUp => kVK_PageUp,
-            Key::Return => kVK_Return,
-            Key::RightArrow => kVK_RightArrow,
-            Key::Shift => kVK_Shift,
-            Key::Space => kVK_Space,
-            Key::Tab => kVK_Tab,
-            Key::UpArrow => kVK_UpArrow,
-            Key::Raw(raw_keycode) => raw_keycode,
-            Key::Layout(c) => self.get_layoutdependent_keycode(c.to_string()),
-
-            Key::Super | Key::Command | Key::Windows | Key::Meta => kVK_Command,
+            Key::Alt => Some(kVK_Option),
+            Key::Backspace => Some(kVK_Delete),
+            Key::CapsLock => Some(kVK_CapsLock),
+            Key::Control => Some(kVK_Control),
+            Key::Delete => Some(kVK_ForwardDelete),
+            Key::DownArrow => Some(kVK_DownArrow),
+            Key::End => Some(kVK_End),
+            Key::Escape => Some(kVK_Escape),
+            Key::F1 => Some(kVK_F1),
+            Key::F10 => Some(kVK_F10),
+            Key::F11 => Some(kVK_F11),
+            Key::F12 => Some(kVK_F12),
+            Key::F2 => Some(kVK_F2),
+            Key::F3 => Some(kVK_F3),
+            Key::F4 => Some(kVK_F4),
+            Key::F5 => Some(kVK_F5),
+            Key::F6 => Some(kVK_F6),
+            Key::F7 => Some(kVK_F7),
+            Key::F8 => Some(kVK_F8),
+            Key::F9 => Some(kVK_F9),
+            Key::Home => Some(kVK_Home),
+            Key::Insert => Some(kVK_Help),
+            Key::LeftArrow => Some(kVK_LeftArrow),
+            Key::Menu => Some(kVK_ContextualMenu),
+            Key::ModeSwitch => Some(kVK_RightOption),
+            Key::NumLock => Some(kVK_ANSI_KeypadClear),
+            Key::Numpad0 => Some(kVK_ANSI_Keypad0),
+            Key::Numpad1 => Some(kVK_ANSI_Keypad1),
+            Key::Numpad2 => Some(kVK_ANSI_Keypad2),
+            Key::Numpad3 => Some(kVK_ANSI_Keypad3),
+            Key::Numpad4 => Some(kVK_ANSI_Keypad4),
+            Key::Numpad5 => Some(kVK_ANSI_Keypad5),
+            Key::Numpad6 => Some(kVK_ANSI_Keypad6),
+            Key::Numpad7 => Some(kVK_ANSI_Keypad7),
+            Key::Numpad8 => Some(kVK_ANSI_Keypad8),
+            Key::Numpad9 => Some(kVK_ANSI_Keypad9),
+            Key::NumpadAdd => Some(kVK_ANSI_KeypadPlus),
+            Key::NumpadDecimal => Some(kVK_ANSI_KeypadDecimal),
+            Key::NumpadDivide => Some(kVK_ANSI_KeypadDivide),
+            Key::NumpadEnter => Some(kVK_ANSI_KeypadEnter),
+            Key::NumpadMultiply => Some(kVK_ANSI_KeypadMultiply),
+            Key::NumpadSubtract => Some(kVK_ANSI_KeypadMinus),
+            Key::Option => Some(kVK_Option),
+            Key::PageDown => Some(kVK_PageDown),
+            Key::PageUp => Some(kVK_PageUp),
+            Key::Return => Some(kVK_Return),
+            Key::RightArrow => Some(kVK_RightArrow),
+            Key::Shift => Some(kVK_Shift),
+            Key::Space => Some(kVK_Space),
+            Key::Tab => Some(kVK_Tab),
+            Key::UpArrow => Some(kVK_UpArrow),
+            Key::VolumeDown => Some(kVK_VolumeDown),
+            Key::VolumeMute => Some(kVK_Mute),
+            Key::VolumeUp => Some(kVK_VolumeUp),
+            Key::Raw(raw_keycode) => Some(raw_keycode),
+            Key::Layout(c) => Some(self.get_layoutdependent_keycode(c.to_string())),
+
+            Key::Super | Key::Command | Key::Windows | Key::Meta => Some(kVK_Command),
         }
     }
 
@@ -551,3 +640,13 @@ impl Enigo {
         unsafe { CFStringCreateWithCharacters(kCFAllocatorDefault, &chars, 1) }
     }
 }
+
+// The NX_KEYTYPE of a media key
+fn media_key_type(key: Key) -> Option<c_long> {
+    match key {
+        Key::MediaNext => Some(NX_KEYTYPE_NEXT),
+        Key::MediaPlayPause => Some(NX_KEYTYPE_PLAY),
+        Key::MediaPrev => Some(NX_KEYTYPE_PREVIOUS),
+        _ => None,
+    }
+}
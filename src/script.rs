@@ -0,0 +1,337 @@
+use crate::{Key, KeyboardControllable, MouseButton, MouseControllable};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+#[cfg(feature = "with_serde")]
+use std::fs::File;
+#[cfg(feature = "with_serde")]
+use std::io::{self, BufReader, BufWriter};
+#[cfg(feature = "with_serde")]
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+/// A single step of an automation script
+#[cfg_attr(feature = "with_serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Move the mouse to absolute coordinates. Script: `move 100,200`
+    MoveTo(i32, i32),
+    /// Move the mouse relative to its position. Script: `move_relative -5,5`
+    MoveRelative(i32, i32),
+    /// Click a mouse button. Script: `click left`
+    Click(MouseButton),
+    /// Push down a mouse button. Script: `mouse_down left`
+    MouseDown(MouseButton),
+    /// Lift up a mouse button. Script: `mouse_up left`
+    MouseUp(MouseButton),
+    /// Scroll horizontally. Script: `scroll_x -2`
+    ScrollX(i32),
+    /// Scroll vertically. Script: `scroll_y 3`
+    ScrollY(i32),
+    /// Click a key. Script: `key Return`
+    KeyClick(Key),
+    /// Press a key down. Script: `key_down Shift`
+    KeyDown(Key),
+    /// Release a key. Script: `key_up Shift`
+    KeyUp(Key),
+    /// Type a string. Script: `type hello world`
+    Type(String),
+    /// Do nothing for a while. Script: `sleep 500` (milliseconds)
+    Sleep(Duration),
+}
+
+/// An error that can occur when parsing a script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptError {
+    /// When a command doesn't exist.
+    /// Example: jump 100,200
+    ///          ^^^^
+    UnknownCommand(String),
+
+    /// When a command is missing one of its arguments.
+    /// Example: move 100
+    ///          ^^^^
+    MissingArgument(String),
+
+    /// When an argument can't be parsed.
+    /// Example: click sideways
+    ///                ^^^^^^^^
+    InvalidArgument(String),
+
+    /// When a command gets more arguments than it takes.
+    /// Example: click left right
+    ///                     ^^^^^
+    UnexpectedArgument(String),
+}
+impl Error for ScriptError {}
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptError::UnknownCommand(token) => write!(f, "Unknown command: {}", token),
+            ScriptError::MissingArgument(token) => write!(f, "Missing argument for: {}", token),
+            ScriptError::InvalidArgument(token) => write!(f, "Invalid argument: {}", token),
+            ScriptError::UnexpectedArgument(token) => write!(f, "Unexpected argument: {}", token),
+        }
+    }
+}
+
+/// Parse a script into a list of actions.
+///
+/// Commands are separated by `;` and take their arguments separated by
+/// spaces or commas, e.g.
+/// `"move 100,200; click left; type hello; key Return; sleep 500"`.
+/// `type` takes the rest of the command verbatim, use `;;` to type a `;`.
+///
+/// | command                 | action                                         |
+/// |-------------------------|------------------------------------------------|
+/// | `move X,Y`              | [MoveTo](enum.Action.html#variant.MoveTo)      |
+/// | `move_relative X,Y`     | [MoveRelative](enum.Action.html#variant.MoveRelative) |
+/// | `click BUTTON`          | [Click](enum.Action.html#variant.Click)        |
+/// | `mouse_down BUTTON`     | [MouseDown](enum.Action.html#variant.MouseDown) |
+/// | `mouse_up BUTTON`       | [MouseUp](enum.Action.html#variant.MouseUp)    |
+/// | `scroll_x N`            | [ScrollX](enum.Action.html#variant.ScrollX)    |
+/// | `scroll_y N`            | [ScrollY](enum.Action.html#variant.ScrollY)    |
+/// | `key NAME`              | [KeyClick](enum.Action.html#variant.KeyClick)  |
+/// | `key_down NAME`         | [KeyDown](enum.Action.html#variant.KeyDown)    |
+/// | `key_up NAME`           | [KeyUp](enum.Action.html#variant.KeyUp)        |
+/// | `type TEXT`             | [Type](enum.Action.html#variant.Type)          |
+/// | `sleep MILLISECONDS`    | [Sleep](enum.Action.html#variant.Sleep)        |
+///
+/// Buttons are `left`, `middle`, `right`, `back` and `forward`. Keys are X
+/// keysym names like `Return`, `BackSpace` or `F5`, or a single character.
+pub fn parse(script: &str) -> Result<Vec<Action>, ScriptError> {
+    let mut actions = Vec::new();
+    for command in split_commands(script) {
+        let command = command.trim_start();
+        if command.trim().is_empty() {
+            continue;
+        }
+        let (name, rest) = match command.find(char::is_whitespace) {
+            Some(index) => {
+                let (name, rest) = command.split_at(index);
+                let mut rest = rest.chars();
+                rest.next();
+                (name, rest.as_str())
+            }
+            None => (command, ""),
+        };
+        if name == "type" {
+            actions.push(Action::Type(rest.to_string()));
+            continue;
+        }
+
+        let mut args = Args {
+            command: name,
+            tokens: rest
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|token| !token.is_empty())
+                .collect::<Vec<_>>()
+                .into_iter(),
+        };
+        let action = match name {
+            "move" => Action::MoveTo(args.number()?, args.number()?),
+            "move_relative" => Action::MoveRelative(args.number()?, args.number()?),
+            "click" => Action::Click(args.button()?),
+            "mouse_down" => Action::MouseDown(args.button()?),
+            "mouse_up" => Action::MouseUp(args.button()?),
+            "scroll_x" => Action::ScrollX(args.number()?),
+            "scroll_y" => Action::ScrollY(args.number()?),
+            "key" => Action::KeyClick(args.key()?),
+            "key_down" => Action::KeyDown(args.key()?),
+            "key_up" => Action::KeyUp(args.key()?),
+            "sleep" => Action::Sleep(Duration::from_millis(args.number()?)),
+            _ => return Err(ScriptError::UnknownCommand(name.to_string())),
+        };
+        args.finish()?;
+        actions.push(action);
+    }
+    Ok(actions)
+}
+
+/// Execute a single action.
+pub fn execute<E>(enigo: &mut E, action: &Action)
+where
+    E: MouseControllable + KeyboardControllable,
+{
+    match *action {
+        Action::MoveTo(x, y) => enigo.mouse_move_to(x, y),
+        Action::MoveRelative(x, y) => enigo.mouse_move_relative(x, y),
+        Action::Click(button) => enigo.mouse_click(button),
+        Action::MouseDown(button) => enigo.mouse_down(button),
+        Action::MouseUp(button) => enigo.mouse_up(button),
+        Action::ScrollX(length) => enigo.mouse_scroll_x(length),
+        Action::ScrollY(length) => enigo.mouse_scroll_y(length),
+        Action::KeyClick(key) => enigo.key_click(key),
+        Action::KeyDown(key) => enigo.key_down(key),
+        Action::KeyUp(key) => enigo.key_up(key),
+        Action::Type(ref text) => enigo.key_sequence(text),
+        Action::Sleep(duration) => thread::sleep(duration),
+    }
+}
+
+/// Execute the actions one after another.
+pub fn run<E>(enigo: &mut E, actions: &[Action])
+where
+    E: MouseControllable + KeyboardControllable,
+{
+    for action in actions {
+        execute(enigo, action);
+    }
+}
+
+/// Save the actions to a macro file at `path`.
+///
+/// The file is a JSON array of actions, one object per action, e.g.
+/// `[{"MoveTo": [100, 200]}, {"Click": "Left"}, {"Type": "hello"},
+/// {"Sleep": {"secs": 0, "nanos": 500000000}}]`, which can be edited by hand
+/// and read back with [load_macro](fn.load_macro.html).
+#[cfg(feature = "with_serde")]
+pub fn save_macro(actions: &[Action], path: &Path) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(file, actions).map_err(io::Error::from)
+}
+
+/// Load the actions from a macro file written by
+/// [save_macro](fn.save_macro.html).
+#[cfg(feature = "with_serde")]
+pub fn load_macro(path: &Path) -> io::Result<Vec<Action>> {
+    let file = BufReader::new(File::open(path)?);
+    serde_json::from_reader(file).map_err(io::Error::from)
+}
+
+// Split on `;` while turning `;;` into a literal `;`
+fn split_commands(script: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut buffer = String::new();
+    let mut iter = script.chars().peekable();
+    while let Some(c) = iter.next() {
+        if c == ';' {
+            if iter.peek() == Some(&';') {
+                iter.next();
+                buffer.push(';');
+            } else {
+                commands.push(std::mem::take(&mut buffer));
+            }
+        } else {
+            buffer.push(c);
+        }
+    }
+    commands.push(buffer);
+    commands
+}
+
+struct Args<'a> {
+    command: &'a str,
+    tokens: std::vec::IntoIter<&'a str>,
+}
+
+impl<'a> Args<'a> {
+    fn next(&mut self) -> Result<&'a str, ScriptError> {
+        self.tokens
+            .next()
+            .ok_or_else(|| ScriptError::MissingArgument(self.command.to_string()))
+    }
+
+    fn number<T: std::str::FromStr>(&mut self) -> Result<T, ScriptError> {
+        let token = self.next()?;
+        token
+            .parse()
+            .map_err(|_| ScriptError::InvalidArgument(token.to_string()))
+    }
+
+    fn button(&mut self) -> Result<MouseButton, ScriptError> {
+        match self.next()? {
+            "left" => Ok(MouseButton::Left),
+            "middle" => Ok(MouseButton::Middle),
+            "right" => Ok(MouseButton::Right),
+            "back" => Ok(MouseButton::Back),
+            "forward" => Ok(MouseButton::Forward),
+            token => Err(ScriptError::InvalidArgument(token.to_string())),
+        }
+    }
+
+    fn key(&mut self) -> Result<Key, ScriptError> {
+        let token = self.next()?;
+        Key::try_from(token).map_err(|_| ScriptError::InvalidArgument(token.to_string()))
+    }
+
+    fn finish(mut self) -> Result<(), ScriptError> {
+        match self.tokens.next() {
+            Some(token) => Err(ScriptError::UnexpectedArgument(token.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn success() {
+        assert_eq!(
+            parse("move 100,200; click left; type hello; key Return; sleep 500"),
+            Ok(vec![
+                Action::MoveTo(100, 200),
+                Action::Click(MouseButton::Left),
+                Action::Type("hello".into()),
+                Action::KeyClick(Key::Return),
+                Action::Sleep(Duration::from_millis(500)),
+            ])
+        );
+    }
+    #[test]
+    fn separators() {
+        assert_eq!(
+            parse(" move_relative -5 , 5 ; ;key_down a;type a;; b;"),
+            Ok(vec![
+                Action::MoveRelative(-5, 5),
+                Action::KeyDown(Key::Layout('a')),
+                Action::Type("a; b".into()),
+            ])
+        );
+    }
+    #[test]
+    fn unknown_command() {
+        assert_eq!(
+            parse("move 1,2; jump 3"),
+            Err(ScriptError::UnknownCommand("jump".into()))
+        );
+    }
+    #[test]
+    fn missing_argument() {
+        assert_eq!(
+            parse("move 100"),
+            Err(ScriptError::MissingArgument("move".into()))
+        );
+    }
+    #[test]
+    fn invalid_argument() {
+        assert_eq!(
+            parse("click sideways"),
+            Err(ScriptError::InvalidArgument("sideways".into()))
+        );
+        assert_eq!(
+            parse("key NotAKey"),
+            Err(ScriptError::InvalidArgument("NotAKey".into()))
+        );
+    }
+    #[test]
+    fn unexpected_argument() {
+        assert_eq!(
+            parse("click left right"),
+            Err(ScriptError::UnexpectedArgument("right".into()))
+        );
+    }
+    #[cfg(feature = "with_serde")]
+    #[test]
+    fn macro_round_trip() {
+        let actions = parse("move 1,2; click right; key_down Shift; type a b; sleep 20").unwrap();
+        let path = std::env::temp_dir().join(format!("enigo-macro-{}.json", std::process::id()));
+        save_macro(&actions, &path).unwrap();
+        let loaded = load_macro(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), actions);
+    }
+}
@@ -9,6 +9,7 @@ pub const EVK_LWIN: u16 = 0x5b;
 pub const EVK_SHIFT: u16 = 0x10;
 pub const EVK_CAPITAL: u16 = 0x14;
 pub const EVK_MENU: u16 = 0x12;
+pub const EVK_RMENU: u16 = 0xa5;
 pub const EVK_LCONTROL: u16 = 0xa2;
 pub const EVK_HOME: u16 = 0x24;
 pub const EVK_PRIOR: u16 = 0x21;
@@ -19,6 +20,34 @@ pub const EVK_RIGHT: u16 = 0x27;
 pub const EVK_UP: u16 = 0x26;
 pub const EVK_DOWN: u16 = 0x28;
 pub const EVK_DELETE: u16 = 0x2E;
+pub const EVK_INSERT: u16 = 0x2D;
+pub const EVK_SNAPSHOT: u16 = 0x2C;
+pub const EVK_PAUSE: u16 = 0x13;
+pub const EVK_SCROLL: u16 = 0x91;
+pub const EVK_NUMLOCK: u16 = 0x90;
+pub const EVK_APPS: u16 = 0x5D;
+pub const EVK_NUMPAD0: u16 = 0x60;
+pub const EVK_NUMPAD1: u16 = 0x61;
+pub const EVK_NUMPAD2: u16 = 0x62;
+pub const EVK_NUMPAD3: u16 = 0x63;
+pub const EVK_NUMPAD4: u16 = 0x64;
+pub const EVK_NUMPAD5: u16 = 0x65;
+pub const EVK_NUMPAD6: u16 = 0x66;
+pub const EVK_NUMPAD7: u16 = 0x67;
+pub const EVK_NUMPAD8: u16 = 0x68;
+pub const EVK_NUMPAD9: u16 = 0x69;
+pub const EVK_MULTIPLY: u16 = 0x6A;
+pub const EVK_ADD: u16 = 0x6B;
+pub const EVK_SUBTRACT: u16 = 0x6D;
+pub const EVK_DECIMAL: u16 = 0x6E;
+pub const EVK_DIVIDE: u16 = 0x6F;
+pub const EVK_VOLUME_MUTE: u16 = 0xAD;
+pub const EVK_VOLUME_DOWN: u16 = 0xAE;
+pub const EVK_VOLUME_UP: u16 = 0xAF;
+pub const EVK_MEDIA_NEXT_TRACK: u16 = 0xB0;
+pub const EVK_MEDIA_PREV_TRACK: u16 = 0xB1;
+pub const EVK_MEDIA_STOP: u16 = 0xB2;
+pub const EVK_MEDIA_PLAY_PAUSE: u16 = 0xB3;
 pub const EVK_F1: u16 = 0x70;
 pub const EVK_F2: u16 = 0x71;
 pub const EVK_F3: u16 = 0x72;
@@ -60,31 +60,27 @@ impl MouseControllable for Enigo {
     }
 
     fn mouse_down(&mut self, button: MouseButton) {
-        mouse_event(
-            match button {
-                MouseButton::Left => MOUSEEVENTF_LEFTDOWN,
-                MouseButton::Middle => MOUSEEVENTF_MIDDLEDOWN,
-                MouseButton::Right => MOUSEEVENTF_RIGHTDOWN,
-                _ => unimplemented!(),
-            },
-            0,
-            0,
-            0,
-        );
+        let (flags, data) = match button {
+            MouseButton::Left => (MOUSEEVENTF_LEFTDOWN, 0),
+            MouseButton::Middle => (MOUSEEVENTF_MIDDLEDOWN, 0),
+            MouseButton::Right => (MOUSEEVENTF_RIGHTDOWN, 0),
+            MouseButton::Back => (MOUSEEVENTF_XDOWN, u32::from(XBUTTON1)),
+            MouseButton::Forward => (MOUSEEVENTF_XDOWN, u32::from(XBUTTON2)),
+            _ => unimplemented!(),
+        };
+        mouse_event(flags, data, 0, 0);
     }
 
     fn mouse_up(&mut self, button: MouseButton) {
-        mouse_event(
-            match button {
-                MouseButton::Left => MOUSEEVENTF_LEFTUP,
-                MouseButton::Middle => MOUSEEVENTF_MIDDLEUP,
-                MouseButton::Right => MOUSEEVENTF_RIGHTUP,
-                _ => unimplemented!(),
-            },
-            0,
-            0,
-            0,
-        );
+        let (flags, data) = match button {
+            MouseButton::Left => (MOUSEEVENTF_LEFTUP, 0),
+            MouseButton::Middle => (MOUSEEVENTF_MIDDLEUP, 0),
+            MouseButton::Right => (MOUSEEVENTF_RIGHTUP, 0),
+            MouseButton::Back => (MOUSEEVENTF_XUP, u32::from(XBUTTON1)),
+            MouseButton::Forward => (MOUSEEVENTF_XUP, u32::from(XBUTTON2)),
+            _ => unimplemented!(),
+        };
+        mouse_event(flags, data, 0, 0);
     }
 
     fn mouse_click(&mut self, button: MouseButton) {
@@ -224,16 +220,46 @@ impl Enigo {
             Key::F8 => EVK_F8,
             Key::F9 => EVK_F9,
             Key::Home => EVK_HOME,
+            Key::Insert => EVK_INSERT,
             Key::LeftArrow => EVK_LEFT,
+            Key::MediaNext => EVK_MEDIA_NEXT_TRACK,
+            Key::MediaPlayPause => EVK_MEDIA_PLAY_PAUSE,
+            Key::MediaPrev => EVK_MEDIA_PREV_TRACK,
+            Key::MediaStop => EVK_MEDIA_STOP,
+            Key::Menu => EVK_APPS,
+            Key::ModeSwitch => EVK_RMENU,
+            Key::NumLock => EVK_NUMLOCK,
+            Key::Numpad0 => EVK_NUMPAD0,
+            Key::Numpad1 => EVK_NUMPAD1,
+            Key::Numpad2 => EVK_NUMPAD2,
+            Key::Numpad3 => EVK_NUMPAD3,
+            Key::Numpad4 => EVK_NUMPAD4,
+            Key::Numpad5 => EVK_NUMPAD5,
+            Key::Numpad6 => EVK_NUMPAD6,
+            Key::Numpad7 => EVK_NUMPAD7,
+            Key::Numpad8 => EVK_NUMPAD8,
+            Key::Numpad9 => EVK_NUMPAD9,
+            Key::NumpadAdd => EVK_ADD,
+            Key::NumpadDecimal => EVK_DECIMAL,
+            Key::NumpadDivide => EVK_DIVIDE,
+            Key::NumpadEnter => EVK_RETURN,
+            Key::NumpadMultiply => EVK_MULTIPLY,
+            Key::NumpadSubtract => EVK_SUBTRACT,
             Key::Option => EVK_MENU,
             Key::PageDown => EVK_NEXT,
             Key::PageUp => EVK_PRIOR,
+            Key::Pause => EVK_PAUSE,
+            Key::PrintScreen => EVK_SNAPSHOT,
             Key::Return => EVK_RETURN,
             Key::RightArrow => EVK_RIGHT,
+            Key::ScrollLock => EVK_SCROLL,
             Key::Shift => EVK_SHIFT,
             Key::Space => EVK_SPACE,
             Key::Tab => EVK_TAB,
             Key::UpArrow => EVK_UP,
+            Key::VolumeDown => EVK_VOLUME_DOWN,
+            Key::VolumeMute => EVK_VOLUME_MUTE,
+            Key::VolumeUp => EVK_VOLUME_UP,
 
             Key::Raw(raw_keycode) => raw_keycode,
             Key::Layout(c) => self.get_layoutdependent_keycode(c.to_string()),